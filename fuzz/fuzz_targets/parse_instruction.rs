@@ -0,0 +1,12 @@
+#![no_main]
+
+use exapunks_lite::instruction::Instruction;
+use libfuzzer_sys::fuzz_target;
+
+// Parsing any line must not panic, and every line that parses must survive a round trip through
+// Display.
+fuzz_target!(|line: &str| {
+    if let Ok(instruction) = line.parse::<Instruction>() {
+        assert_eq!(instruction.to_string().parse(), Ok(instruction));
+    }
+});
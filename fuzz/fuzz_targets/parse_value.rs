@@ -0,0 +1,16 @@
+#![no_main]
+
+use exapunks_lite::value::Value;
+use libfuzzer_sys::fuzz_target;
+
+// None of the Value constructors may panic, and every parsed Value must survive a round trip
+// through Display.
+fuzz_target!(|input: &str| {
+    let _ = Value::new_number_or_register_id(input);
+    let _ = Value::new_register_id(input);
+    let _ = Value::new_label_id(input);
+
+    if let Ok(value) = input.parse::<Value>() {
+        assert_eq!(value.to_string().parse(), Ok(value));
+    }
+});
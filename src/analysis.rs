@@ -0,0 +1,593 @@
+use std::fmt;
+
+use crate::diagnostic::{Diagnostic, DiagnosticCode};
+use crate::instruction::Instruction;
+use crate::value::Value;
+
+/// A suggestion for improving a program's score.
+///
+/// Hints are only suggestions and are never applied automatically. For example, a NOOP might be
+/// there on purpose to line up timing with another EXA.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Hint {
+    /// The 1-based position of the instruction in the analyzed slice.
+    pub line: usize,
+    /// What could be improved.
+    pub kind: HintKind,
+}
+
+/// The kinds of improvements [`optimization_hints`] looks for.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HintKind {
+    /// A NOOP, which only spends a cycle.
+    NoOp,
+    /// A COPY from a register to itself, which has no effect.
+    RedundantCopy,
+    /// A jump to the instruction that would run next anyway.
+    JumpToNextLine,
+    /// A TEST whose result in T is overwritten on the given line before anything reads it.
+    UnusedTest { overwritten_on_line: usize },
+    /// An instruction that does the same thing every pass through the loop starting at the given
+    /// label, so it could be moved before the loop.
+    LoopInvariant { label: Value },
+}
+
+impl Diagnostic for Hint {
+    fn diagnostic_code(&self) -> DiagnosticCode {
+        match self.kind {
+            HintKind::NoOp => DiagnosticCode::NoOpHint,
+            HintKind::RedundantCopy => DiagnosticCode::RedundantCopyHint,
+            HintKind::JumpToNextLine => DiagnosticCode::JumpToNextLineHint,
+            HintKind::UnusedTest { .. } => DiagnosticCode::UnusedTestHint,
+            HintKind::LoopInvariant { .. } => DiagnosticCode::LoopInvariantHint,
+        }
+    }
+}
+
+impl fmt::Display for Hint {
+    /// Writes the line and the catalog message, followed by the overwriting line or the loop's
+    /// label in parentheses where there is one, e.g. "line 3: TEST result is overwritten before
+    /// it is used (line 5)".
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.diagnostic_code())?;
+
+        match &self.kind {
+            HintKind::UnusedTest {
+                overwritten_on_line,
+            } => write!(f, " (line {overwritten_on_line})"),
+            HintKind::LoopInvariant { label } => write!(f, " ({label})"),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Looks for ways to improve the score of the given program, ordered by line.
+///
+/// The analysis is purely static, so it only reports patterns that are safe to flag without
+/// running the program:
+///
+/// * NOOP instructions.
+/// * COPY from X or T to itself.
+/// * JUMP, TJMP, or FJMP to a label that only has MARKs or NOTEs between it and the jump.
+/// * TEST results overwritten before any instruction reads T.
+/// * Instructions in a loop that write X or T from numbers only, when nothing else in the loop
+///   writes that register, nothing earlier in the loop reads it or jumps, and the loop can only
+///   be entered through its MARK.
+///
+/// # Examples
+///
+/// ```
+/// # use exapunks_lite::analysis::{optimization_hints, HintKind};
+/// # use exapunks_lite::instruction::Instruction;
+/// let program: Vec<Instruction> = ["COPY 1 X", "NOOP", "COPY X X"]
+///     .iter()
+///     .map(|line| line.parse().unwrap())
+///     .collect();
+///
+/// let kinds: Vec<HintKind> = optimization_hints(&program)
+///     .into_iter()
+///     .map(|hint| hint.kind)
+///     .collect();
+///
+/// assert_eq!(kinds, vec![HintKind::NoOp, HintKind::RedundantCopy]);
+/// ```
+#[must_use]
+pub fn optimization_hints(instructions: &[Instruction]) -> Vec<Hint> {
+    let mut hints = Vec::new();
+
+    for (index, instruction) in instructions.iter().enumerate() {
+        let mut add_hint = |kind| {
+            hints.push(Hint {
+                line: index + 1,
+                kind,
+            });
+        };
+
+        match instruction {
+            Instruction::NoOp => add_hint(HintKind::NoOp),
+            Instruction::Copy(source, destination)
+                if (source == destination) && is_plain_register(destination) =>
+            {
+                add_hint(HintKind::RedundantCopy);
+            }
+            Instruction::Jump(label)
+            | Instruction::JumpIfTrue(label)
+            | Instruction::JumpIfFalse(label)
+                if jumps_to_next_line(instructions, index, label) =>
+            {
+                add_hint(HintKind::JumpToNextLine);
+            }
+            _ if is_test(instruction) => {
+                if let Some(overwritten_index) = find_unused_test(instructions, index) {
+                    add_hint(HintKind::UnusedTest {
+                        overwritten_on_line: overwritten_index + 1,
+                    });
+                }
+            }
+            _ => {}
+        }
+    }
+
+    hints.extend(find_loop_invariants(instructions));
+    hints.sort_by_key(|hint| hint.line);
+
+    hints
+}
+
+/// Returns true for registers that can be read and written without side effects (X and T).
+fn is_plain_register(value: &Value) -> bool {
+    matches!(value, Value::RegisterId(id) if matches!(id.as_str(), "X" | "T"))
+}
+
+/// Returns true if the instruction never runs (MARK and NOTE).
+fn is_non_executing(instruction: &Instruction) -> bool {
+    matches!(instruction, Instruction::Mark(_) | Instruction::Note)
+}
+
+/// Returns true if the instruction sets T as a test result.
+fn is_test(instruction: &Instruction) -> bool {
+    matches!(
+        instruction,
+        Instruction::TestEqual(..)
+            | Instruction::TestGreaterThan(..)
+            | Instruction::TestLessThan(..)
+            | Instruction::TestMRD
+            | Instruction::TestEndOfFile
+    )
+}
+
+/// Returns the values an instruction reads.
+fn sources(instruction: &Instruction) -> Vec<&Value> {
+    match instruction {
+        Instruction::Copy(source, _)
+        | Instruction::Link(source)
+        | Instruction::Grab(source)
+        | Instruction::Seek(source) => vec![source],
+        Instruction::Add(first, second, _)
+        | Instruction::Subtract(first, second, _)
+        | Instruction::Multiply(first, second, _)
+        | Instruction::Divide(first, second, _)
+        | Instruction::Modulo(first, second, _)
+        | Instruction::Swiz(first, second, _)
+        | Instruction::Random(first, second, _)
+        | Instruction::TestEqual(first, second)
+        | Instruction::TestGreaterThan(first, second)
+        | Instruction::TestLessThan(first, second) => vec![first, second],
+        _ => Vec::new(),
+    }
+}
+
+/// Returns the register id an instruction writes to, if any.
+fn destination(instruction: &Instruction) -> Option<&str> {
+    match instruction {
+        Instruction::Copy(_, Value::RegisterId(id))
+        | Instruction::Add(_, _, Value::RegisterId(id))
+        | Instruction::Subtract(_, _, Value::RegisterId(id))
+        | Instruction::Multiply(_, _, Value::RegisterId(id))
+        | Instruction::Divide(_, _, Value::RegisterId(id))
+        | Instruction::Modulo(_, _, Value::RegisterId(id))
+        | Instruction::Swiz(_, _, Value::RegisterId(id))
+        | Instruction::Random(_, _, Value::RegisterId(id))
+        | Instruction::Host(Value::RegisterId(id))
+        | Instruction::File(Value::RegisterId(id)) => Some(id.as_str()),
+        _ if is_test(instruction) => Some("T"),
+        _ => None,
+    }
+}
+
+/// Returns true if the instruction reads the given register.
+///
+/// TJMP and FJMP read T, and REPL reads every register since it copies them to the new EXA.
+fn reads_register(instruction: &Instruction, register_id: &str) -> bool {
+    let reads_implicitly = matches!(instruction, Instruction::Replicate(_))
+        || ((register_id == "T")
+            && matches!(
+                instruction,
+                Instruction::JumpIfTrue(_) | Instruction::JumpIfFalse(_)
+            ));
+
+    reads_implicitly
+        || sources(instruction)
+            .into_iter()
+            .any(|source| matches!(source, Value::RegisterId(id) if *id == register_id))
+}
+
+/// Returns true if only MARKs or NOTEs sit between the jump and the MARK for its label.
+fn jumps_to_next_line(instructions: &[Instruction], jump_index: usize, label: &Value) -> bool {
+    instructions[(jump_index + 1)..]
+        .iter()
+        .take_while(|instruction| is_non_executing(instruction))
+        .any(|instruction| matches!(instruction, Instruction::Mark(mark) if mark == label))
+}
+
+/// Returns true if the instruction is a JUMP, TJMP, or FJMP to the given label.
+fn jumps_to(instruction: &Instruction, label: &Value) -> bool {
+    matches!(
+        instruction,
+        Instruction::Jump(target)
+            | Instruction::JumpIfTrue(target)
+            | Instruction::JumpIfFalse(target)
+            if target == label
+    )
+}
+
+/// Follows the code after a TEST and returns the index of the instruction that overwrites T, if
+/// that happens before anything reads T.
+///
+/// Only straight-line code is followed, so the search stops at JUMP and HALT.
+fn find_unused_test(instructions: &[Instruction], test_index: usize) -> Option<usize> {
+    for (index, instruction) in instructions.iter().enumerate().skip(test_index + 1) {
+        if reads_register(instruction, "T") {
+            return None;
+        } else if destination(instruction) == Some("T") {
+            return Some(index);
+        } else if matches!(instruction, Instruction::Jump(_) | Instruction::Halt) {
+            return None;
+        }
+    }
+
+    None
+}
+
+/// Finds instructions in loops that write X or T using only numbers, where nothing else in the
+/// loop writes that register and nothing earlier in the loop reads it.
+///
+/// A loop is a MARK followed later by a JUMP, TJMP, or FJMP back to it. When there are several
+/// jumps back to the same MARK, the loop runs up to the last one, since every path back through
+/// the MARK has to be invariant.
+///
+/// Every pass has to run the instruction for the move to be safe, so a jump earlier in the loop
+/// rules it out, since it could leave the loop or skip ahead. So does a MARK inside the loop that
+/// is jumped to from outside, since entering there skips the top of the loop.
+fn find_loop_invariants(instructions: &[Instruction]) -> Vec<Hint> {
+    let mut hints = Vec::new();
+
+    for (mark_index, mark) in instructions.iter().enumerate() {
+        let Instruction::Mark(label) = mark else {
+            continue;
+        };
+
+        let last_jump_index = instructions
+            .iter()
+            .rposition(|instruction| jumps_to(instruction, label))
+            .filter(|&index| index > mark_index);
+
+        let Some(last_jump_index) = last_jump_index else {
+            continue;
+        };
+
+        let body = &instructions[(mark_index + 1)..=last_jump_index];
+
+        let is_entered_from_outside = body.iter().any(|instruction| {
+            let Instruction::Mark(inner_label) = instruction else {
+                return false;
+            };
+
+            instructions.iter().enumerate().any(|(index, other)| {
+                (index < mark_index || index > last_jump_index) && jumps_to(other, inner_label)
+            })
+        });
+
+        if is_entered_from_outside {
+            continue;
+        }
+
+        for (offset, instruction) in body.iter().enumerate() {
+            let Some(register_id) = destination(instruction) else {
+                continue;
+            };
+
+            let is_constant = !is_test(instruction)
+                && !matches!(instruction, Instruction::Random(..))
+                && sources(instruction)
+                    .iter()
+                    .all(|source| matches!(source, Value::Number(_)));
+            let is_only_write = body.iter().enumerate().all(|(other, other_instruction)| {
+                (other == offset) || (destination(other_instruction) != Some(register_id))
+            });
+            let is_read_before = body[..offset]
+                .iter()
+                .any(|earlier| reads_register(earlier, register_id));
+            let is_jump_before = body[..offset].iter().any(|earlier| {
+                matches!(
+                    earlier,
+                    Instruction::Jump(_) | Instruction::JumpIfTrue(_) | Instruction::JumpIfFalse(_)
+                )
+            });
+
+            if matches!(register_id, "X" | "T")
+                && is_constant
+                && is_only_write
+                && !is_read_before
+                && !is_jump_before
+            {
+                hints.push(Hint {
+                    line: mark_index + offset + 2,
+                    kind: HintKind::LoopInvariant {
+                        label: label.clone(),
+                    },
+                });
+            }
+        }
+    }
+
+    hints
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{optimization_hints, Hint, HintKind};
+    use crate::diagnostic::{Diagnostic, DiagnosticCode};
+    use crate::instruction::Instruction;
+    use crate::value::Value;
+
+    fn parse(lines: &[&str]) -> Vec<Instruction> {
+        lines.iter().map(|line| line.parse().unwrap()).collect()
+    }
+
+    #[test]
+    fn test_no_hints() {
+        let program = parse(&[
+            "COPY 1 X",
+            "MARK LOOP",
+            "ADDI X 1 X",
+            "TEST X = 10",
+            "FJMP LOOP",
+        ]);
+
+        let hints = optimization_hints(&program);
+
+        assert!(hints.is_empty());
+    }
+
+    #[test]
+    fn test_noop() {
+        let program = parse(&["COPY 1 X", "NOOP"]);
+
+        let expected = vec![Hint {
+            line: 2,
+            kind: HintKind::NoOp,
+        }];
+
+        assert_eq!(optimization_hints(&program), expected);
+    }
+
+    #[test]
+    fn test_redundant_copy() {
+        let program = parse(&[
+            "COPY X X",
+            "COPY T T",
+            "COPY M M",
+            "COPY F F",
+            "COPY #NERV #NERV",
+        ]);
+
+        let expected = vec![
+            Hint {
+                line: 1,
+                kind: HintKind::RedundantCopy,
+            },
+            Hint {
+                line: 2,
+                kind: HintKind::RedundantCopy,
+            },
+        ];
+
+        assert_eq!(optimization_hints(&program), expected);
+    }
+
+    #[test]
+    fn test_jump_to_next_line() {
+        let program = parse(&[
+            "JUMP NEXT",
+            "NOTE SKIPPED",
+            "MARK OTHER",
+            "MARK NEXT",
+            "TEST X = 0",
+            "TJMP END",
+            "COPY 1 X",
+            "MARK END",
+        ]);
+
+        let expected = vec![Hint {
+            line: 1,
+            kind: HintKind::JumpToNextLine,
+        }];
+
+        assert_eq!(optimization_hints(&program), expected);
+    }
+
+    #[test]
+    fn test_unused_test() {
+        let program = parse(&[
+            "TEST X = 0",
+            "COPY 1 X",
+            "TEST X > 5",
+            "FJMP END",
+            "MARK END",
+        ]);
+
+        let expected = vec![
+            Hint {
+                line: 1,
+                kind: HintKind::UnusedTest {
+                    overwritten_on_line: 3,
+                },
+            },
+            Hint {
+                line: 4,
+                kind: HintKind::JumpToNextLine,
+            },
+        ];
+
+        assert_eq!(optimization_hints(&program), expected);
+    }
+
+    #[test]
+    fn test_unused_test_stops_at_read_or_jump() {
+        let program = parse(&[
+            "TEST X = 0",
+            "ADDI T 1 T",
+            "TEST EOF",
+            "REPL CHILD",
+            "TEST MRD",
+            "JUMP ELSEWHERE",
+            "COPY 0 T",
+            "MARK CHILD",
+            "MARK ELSEWHERE",
+        ]);
+
+        let hints = optimization_hints(&program);
+
+        assert!(hints
+            .iter()
+            .all(|hint| !matches!(hint.kind, HintKind::UnusedTest { .. })));
+    }
+
+    #[test]
+    fn test_loop_invariant() {
+        let program = parse(&[
+            "MARK LOOP",
+            "COPY 5 X",
+            "ADDI T X T",
+            "TEST T > 100",
+            "FJMP LOOP",
+            "TJMP LOOP",
+        ]);
+
+        let expected = vec![Hint {
+            line: 2,
+            kind: HintKind::LoopInvariant {
+                label: Value::LabelId("LOOP".into()),
+            },
+        }];
+
+        assert_eq!(optimization_hints(&program), expected);
+    }
+
+    #[test]
+    fn test_loop_invariant_ignores_registers_read_or_written_elsewhere() {
+        let program = parse(&[
+            "MARK LOOP",
+            "ADDI X 1 T",
+            "COPY 5 X",
+            "COPY 6 T",
+            "COPY 7 T",
+            "COPY 1 #NERV",
+            "JUMP LOOP",
+        ]);
+
+        let hints = optimization_hints(&program);
+
+        assert!(hints
+            .iter()
+            .all(|hint| !matches!(hint.kind, HintKind::LoopInvariant { .. })));
+    }
+
+    #[test]
+    fn test_loop_invariant_ignores_writes_after_an_early_exit() {
+        let program = parse(&[
+            "MARK LOOP",
+            "TEST F = 0",
+            "TJMP DONE",
+            "COPY 1 X",
+            "COPY X #NERV",
+            "JUMP LOOP",
+            "MARK DONE",
+            "COPY X #NERV",
+        ]);
+
+        let hints = optimization_hints(&program);
+
+        assert!(hints
+            .iter()
+            .all(|hint| !matches!(hint.kind, HintKind::LoopInvariant { .. })));
+    }
+
+    #[test]
+    fn test_loop_invariant_ignores_writes_that_can_be_skipped() {
+        let program = parse(&[
+            "MARK LOOP",
+            "TJMP SKIP",
+            "COPY 5 X",
+            "MARK SKIP",
+            "COPY X M",
+            "JUMP LOOP",
+        ]);
+
+        let hints = optimization_hints(&program);
+
+        assert!(hints
+            .iter()
+            .all(|hint| !matches!(hint.kind, HintKind::LoopInvariant { .. })));
+    }
+
+    #[test]
+    fn test_loop_invariant_ignores_loops_entered_from_outside() {
+        let program = parse(&[
+            "JUMP IN",
+            "MARK LOOP",
+            "COPY 5 X",
+            "MARK IN",
+            "COPY X M",
+            "JUMP LOOP",
+        ]);
+
+        let hints = optimization_hints(&program);
+
+        assert!(hints
+            .iter()
+            .all(|hint| !matches!(hint.kind, HintKind::LoopInvariant { .. })));
+    }
+
+    #[test]
+    fn test_loop_invariant_uses_the_longest_loop() {
+        let program = parse(&[
+            "MARK LOOP",
+            "COPY 5 X",
+            "TJMP LOOP",
+            "COPY 6 X",
+            "JUMP LOOP",
+        ]);
+
+        let hints = optimization_hints(&program);
+
+        assert!(hints.is_empty());
+    }
+
+    #[test]
+    fn test_display() {
+        let hint = Hint {
+            line: 3,
+            kind: HintKind::UnusedTest {
+                overwritten_on_line: 5,
+            },
+        };
+
+        assert_eq!(hint.diagnostic_code(), DiagnosticCode::UnusedTestHint);
+        assert_eq!(
+            hint.to_string(),
+            "line 3: TEST result is overwritten before it is used (line 5)"
+        );
+    }
+}
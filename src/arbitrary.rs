@@ -0,0 +1,152 @@
+//! [`Arbitrary`] implementations and strategies for property-based testing with [`proptest`].
+//!
+//! Everything generated here is valid in its context: numbers are within the game's -9999 to 9999
+//! range, register ids are either an EXA register or a four-character hardware register, and
+//! every [`Instruction`] has operands that the parser would accept.
+
+use proptest::arbitrary::Arbitrary;
+use proptest::prelude::*;
+use proptest::strategy::BoxedStrategy;
+
+use crate::exa::CommunicationMode;
+use crate::instruction::Instruction;
+use crate::value::Value;
+
+/// Generates a [`Value::Number`] in the game's range.
+pub fn number() -> impl Strategy<Value = Value> {
+    (-9999_isize..=9999).prop_map(Value::Number)
+}
+
+/// Generates a [`Value::Keyword`] that doesn't parse as a number.
+pub fn keyword() -> impl Strategy<Value = Value> {
+    "[A-Z][A-Z0-9_]{0,11}".prop_map(|keyword| Value::Keyword(keyword.into()))
+}
+
+/// Generates a [`Value::RegisterId`] for either an EXA register or a hardware register.
+pub fn register_id() -> impl Strategy<Value = Value> {
+    prop_oneof![
+        prop_oneof![Just("X"), Just("T"), Just("F"), Just("M")].prop_map(String::from),
+        "#[A-Z]{4}",
+    ]
+    .prop_map(|register_id| Value::RegisterId(register_id.into()))
+}
+
+/// Generates a [`Value::LabelId`].
+pub fn label_id() -> impl Strategy<Value = Value> {
+    "[A-Z0-9_]{1,12}".prop_map(|label_id| Value::LabelId(label_id.into()))
+}
+
+/// Generates either a [`Value::Number`] or a [`Value::RegisterId`], which is what most
+/// instruction sources accept.
+pub fn number_or_register_id() -> impl Strategy<Value = Value> {
+    prop_oneof![number(), register_id()]
+}
+
+impl Arbitrary for Value {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Value>;
+
+    fn arbitrary_with(_args: ()) -> Self::Strategy {
+        prop_oneof![number(), keyword(), register_id(), label_id()].boxed()
+    }
+}
+
+impl Arbitrary for CommunicationMode {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<CommunicationMode>;
+
+    fn arbitrary_with(_args: ()) -> Self::Strategy {
+        prop_oneof![
+            Just(CommunicationMode::Global),
+            Just(CommunicationMode::Local)
+        ]
+        .boxed()
+    }
+}
+
+impl Arbitrary for Instruction {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Instruction>;
+
+    fn arbitrary_with(_args: ()) -> Self::Strategy {
+        let rn_r = || (number_or_register_id(), register_id());
+        let rn_rn_r = || {
+            (
+                number_or_register_id(),
+                number_or_register_id(),
+                register_id(),
+            )
+        };
+        let rn_rn = || (number_or_register_id(), number_or_register_id());
+
+        prop_oneof![
+            rn_r().prop_map(|(src, dest)| Instruction::Copy(src, dest)),
+            rn_rn_r().prop_map(|(src1, src2, dest)| Instruction::Add(src1, src2, dest)),
+            rn_rn_r().prop_map(|(src1, src2, dest)| Instruction::Subtract(src1, src2, dest)),
+            rn_rn_r().prop_map(|(src1, src2, dest)| Instruction::Multiply(src1, src2, dest)),
+            rn_rn_r().prop_map(|(src1, src2, dest)| Instruction::Divide(src1, src2, dest)),
+            rn_rn_r().prop_map(|(src1, src2, dest)| Instruction::Modulo(src1, src2, dest)),
+            rn_rn_r().prop_map(|(src1, src2, dest)| Instruction::Swiz(src1, src2, dest)),
+            label_id().prop_map(Instruction::Mark),
+            label_id().prop_map(Instruction::Jump),
+            label_id().prop_map(Instruction::JumpIfTrue),
+            label_id().prop_map(Instruction::JumpIfFalse),
+            rn_rn().prop_map(|(src1, src2)| Instruction::TestEqual(src1, src2)),
+            rn_rn().prop_map(|(src1, src2)| Instruction::TestGreaterThan(src1, src2)),
+            rn_rn().prop_map(|(src1, src2)| Instruction::TestLessThan(src1, src2)),
+            label_id().prop_map(Instruction::Replicate),
+            Just(Instruction::Halt),
+            Just(Instruction::Kill),
+            number_or_register_id().prop_map(Instruction::Link),
+            register_id().prop_map(Instruction::Host),
+            Just(Instruction::Mode),
+            Just(Instruction::VoidM),
+            Just(Instruction::TestMRD),
+            Just(Instruction::Make),
+            number_or_register_id().prop_map(Instruction::Grab),
+            register_id().prop_map(Instruction::File),
+            number_or_register_id().prop_map(Instruction::Seek),
+            Just(Instruction::VoidF),
+            Just(Instruction::Drop),
+            Just(Instruction::Wipe),
+            Just(Instruction::TestEndOfFile),
+            Just(Instruction::Note),
+            Just(Instruction::NoOp),
+            rn_rn_r().prop_map(|(src1, src2, dest)| Instruction::Random(src1, src2, dest)),
+        ]
+        .boxed()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use crate::instruction::Instruction;
+    use crate::value::Value;
+
+    proptest! {
+        #[test]
+        fn test_value_display_round_trip(value: Value) {
+            let round_trip = match &value {
+                Value::RegisterId(_) => Value::new_register_id(&value.to_string()),
+                Value::LabelId(_) => Value::new_label_id(&value.to_string()),
+                _ => value.to_string().parse(),
+            };
+
+            prop_assert_eq!(round_trip, Ok(value));
+        }
+
+        #[test]
+        fn test_instruction_display_round_trip(instruction: Instruction) {
+            prop_assert_eq!(instruction.to_string().parse(), Ok(instruction));
+        }
+
+        #[test]
+        fn test_number_or_register_id_accepts_generated(
+            value in super::number_or_register_id()
+        ) {
+            prop_assert_eq!(Value::new_number_or_register_id(&value.to_string()), Ok(value));
+        }
+    }
+}
@@ -0,0 +1,207 @@
+use std::error::Error;
+use std::fmt;
+
+use crate::diagnostic::{Diagnostic, DiagnosticCode};
+use crate::value::{Value, MAX_NUMBER, MIN_NUMBER};
+
+/// The most values a `.dat` file can hold. This keeps a huge repetition count from exhausting
+/// memory.
+pub const MAX_FILE_LENGTH: usize = 9999;
+
+/// The reasons a `.dat` file can fail to parse.
+///
+/// Each error holds the 1-based line number of the offending line.
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub enum DataFileError {
+    /// A line with something other than `VALUE` or `VALUE * COUNT`.
+    InvalidLine(usize),
+    /// A repetition count that isn't a positive number.
+    InvalidCount(usize),
+    /// A number outside -9999 to 9999.
+    NumberOutOfRange(usize),
+    /// A line that would take the file past [`MAX_FILE_LENGTH`] values.
+    FileTooLong(usize),
+}
+
+impl DataFileError {
+    /// Returns the 1-based line number of the offending line.
+    #[must_use]
+    pub fn line(&self) -> usize {
+        match self {
+            Self::InvalidLine(line)
+            | Self::InvalidCount(line)
+            | Self::NumberOutOfRange(line)
+            | Self::FileTooLong(line) => *line,
+        }
+    }
+}
+
+impl Diagnostic for DataFileError {
+    fn diagnostic_code(&self) -> DiagnosticCode {
+        match self {
+            Self::InvalidLine(_) => DiagnosticCode::InvalidDataLine,
+            Self::InvalidCount(_) => DiagnosticCode::InvalidDataCount,
+            Self::NumberOutOfRange(_) => DiagnosticCode::DataNumberOutOfRange,
+            Self::FileTooLong(_) => DiagnosticCode::DataFileTooLong,
+        }
+    }
+}
+
+impl fmt::Display for DataFileError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line(), self.diagnostic_code())
+    }
+}
+
+impl Error for DataFileError {}
+
+/// Parses the contents of a `.dat` file into the values of a file.
+///
+/// A `.dat` file holds one number or keyword per line. A value followed by `* COUNT` is repeated
+/// that many times. Blank lines are ignored.
+///
+/// # Errors
+///
+/// Returns an error if a line has extra words, a repetition count that isn't a positive number, or
+/// a number outside -9999 to 9999, or if the file would hold more than [`MAX_FILE_LENGTH`] values.
+///
+/// # Examples
+///
+/// ```
+/// # use exapunks_lite::data_file::parse_data;
+/// # use exapunks_lite::value::Value;
+/// let contents = "KEYWORD\n\n0 * 3\n-9999";
+///
+/// let expected = vec![
+///     Value::Keyword("KEYWORD".into()),
+///     Value::Number(0),
+///     Value::Number(0),
+///     Value::Number(0),
+///     Value::Number(-9999),
+/// ];
+///
+/// assert_eq!(parse_data(contents), Ok(expected));
+/// ```
+pub fn parse_data(contents: &str) -> Result<Vec<Value>, DataFileError> {
+    let mut values = Vec::new();
+
+    for (index, line) in contents.lines().enumerate() {
+        let line_number = index + 1;
+        let words: Vec<&str> = line.split_whitespace().collect();
+
+        let (value, count) = match words[..] {
+            [] => continue,
+            [value] => (value, 1),
+            [value, "*", count] => {
+                let count = count
+                    .parse::<usize>()
+                    .ok()
+                    .filter(|&count| count > 0)
+                    .ok_or(DataFileError::InvalidCount(line_number))?;
+
+                (value, count)
+            }
+            _ => return Err(DataFileError::InvalidLine(line_number)),
+        };
+
+        let value = value
+            .parse::<Value>()
+            .map_err(|_| DataFileError::InvalidLine(line_number))?;
+
+        if matches!(value, Value::Number(number) if !(MIN_NUMBER..=MAX_NUMBER).contains(&number)) {
+            return Err(DataFileError::NumberOutOfRange(line_number));
+        }
+
+        if count > MAX_FILE_LENGTH - values.len() {
+            return Err(DataFileError::FileTooLong(line_number));
+        }
+
+        values.extend(std::iter::repeat_n(value, count));
+    }
+
+    Ok(values)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_data, DataFileError, MAX_FILE_LENGTH};
+    use crate::value::Value;
+
+    #[test]
+    fn test_parse_data() {
+        let contents = "1\nKEYWORD * 2\n  -5  \n";
+
+        let expected = Ok(vec![
+            Value::Number(1),
+            Value::Keyword("KEYWORD".into()),
+            Value::Keyword("KEYWORD".into()),
+            Value::Number(-5),
+        ]);
+
+        assert_eq!(parse_data(contents), expected);
+    }
+
+    #[test]
+    fn test_parse_empty() {
+        assert_eq!(parse_data(""), Ok(Vec::new()));
+        assert_eq!(parse_data("\n  \n"), Ok(Vec::new()));
+    }
+
+    #[test]
+    fn test_parse_invalid_line() {
+        assert_eq!(
+            parse_data("1\nTWO WORDS"),
+            Err(DataFileError::InvalidLine(2))
+        );
+        assert_eq!(parse_data("1 * 2 * 3"), Err(DataFileError::InvalidLine(1)));
+    }
+
+    #[test]
+    fn test_parse_invalid_count() {
+        assert_eq!(parse_data("1 * 0"), Err(DataFileError::InvalidCount(1)));
+        assert_eq!(parse_data("1 * -2"), Err(DataFileError::InvalidCount(1)));
+        assert_eq!(parse_data("1 * X"), Err(DataFileError::InvalidCount(1)));
+        assert_eq!(
+            parse_data("0 * 18446744073709551616"),
+            Err(DataFileError::InvalidCount(1))
+        );
+    }
+
+    #[test]
+    fn test_parse_number_out_of_range() {
+        assert_eq!(
+            parse_data("-9999\n9999"),
+            Ok(vec![Value::Number(-9999), Value::Number(9999)])
+        );
+        assert_eq!(
+            parse_data("1\n99999"),
+            Err(DataFileError::NumberOutOfRange(2))
+        );
+        assert_eq!(
+            parse_data("-10000"),
+            Err(DataFileError::NumberOutOfRange(1))
+        );
+    }
+
+    #[test]
+    fn test_parse_file_too_long() {
+        let contents = format!("1 * {MAX_FILE_LENGTH}");
+
+        assert_eq!(
+            parse_data(&contents).map(|values| values.len()),
+            Ok(MAX_FILE_LENGTH)
+        );
+        assert_eq!(
+            parse_data(&format!("{contents}\n2")),
+            Err(DataFileError::FileTooLong(2))
+        );
+        assert_eq!(
+            parse_data("0 * 18446744073709551615"),
+            Err(DataFileError::FileTooLong(1))
+        );
+        assert_eq!(
+            parse_data("1 * 10000000000"),
+            Err(DataFileError::FileTooLong(1))
+        );
+    }
+}
@@ -0,0 +1,143 @@
+use std::fmt;
+
+/// Declares [`DiagnosticCode`] from a single table, so the enum, its catalog entries, and
+/// [`DiagnosticCode::ALL`] can't drift apart.
+macro_rules! diagnostic_codes {
+    ($($(#[doc = $doc:literal])* $variant:ident => ($code:literal, $message:literal),)*) => {
+        /// A stable code for every user-facing diagnostic in the crate.
+        ///
+        /// Codes starting with E are errors, and codes starting with W are optimization hints
+        /// from [`optimization_hints`](crate::analysis::optimization_hints). The codes never
+        /// change meaning, so front-ends can map them to their own wording or translations, and
+        /// tests can assert on them instead of English text. The English text in
+        /// [`DiagnosticCode::message`] is the default catalog used by the `Display`
+        /// implementations.
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+        pub enum DiagnosticCode {
+            $($(#[doc = $doc])* $variant,)*
+        }
+
+        impl DiagnosticCode {
+            /// Every code, in declaration order.
+            pub const ALL: &'static [DiagnosticCode] = &[$(DiagnosticCode::$variant,)*];
+
+            /// Returns the code and the default English message.
+            fn entry(self) -> (&'static str, &'static str) {
+                match self {
+                    $(Self::$variant => ($code, $message),)*
+                }
+            }
+        }
+    };
+}
+
+diagnostic_codes! {
+    /// E0001: A value failed to parse.
+    InvalidValue => ("E0001", "invalid value"),
+    /// E0002: A non-number value was used where a number was needed.
+    NotANumber => ("E0002", "value is not a number"),
+    /// E0003: DIVI or MODI with a divisor of 0.
+    DivideByZero => ("E0003", "cannot divide by zero"),
+    /// E0101: An unknown or malformed instruction.
+    InvalidInstruction => ("E0101", "unknown or malformed instruction"),
+    /// E0102: An instruction has the wrong number of operands.
+    InvalidLineLength => ("E0102", "wrong number of operands for instruction"),
+    /// E0103: An instruction has an invalid operand.
+    InvalidOperand => ("E0103", "invalid operand for instruction"),
+    /// E0104: A TEST has an operation other than '=', '>', or '<'.
+    InvalidTestOperation => ("E0104", "TEST operation must be '=', '>', or '<'"),
+    /// E0105: A TEST has no operation.
+    MissingTestOperation => ("E0105", "TEST is missing its operation"),
+    /// E0201: An `@IF` has no flag.
+    MissingFlag => ("E0201", "@IF is missing its flag"),
+    /// E0202: An `@ELSE` has no matching `@IF`.
+    UnmatchedElse => ("E0202", "@ELSE without a matching @IF"),
+    /// E0203: An `@IF` has more than one `@ELSE`.
+    DuplicateElse => ("E0203", "@IF already has an @ELSE"),
+    /// E0204: An `@END` has no matching `@IF`.
+    UnmatchedEnd => ("E0204", "@END without a matching @IF"),
+    /// E0205: An `@IF` is never closed.
+    UnterminatedIf => ("E0205", "@IF is never closed by @END"),
+    /// E0206: A line starts with an unknown directive.
+    UnknownDirective => ("E0206", "unknown directive"),
+    /// E0207: A directive is followed by extra words.
+    UnexpectedToken => ("E0207", "unexpected word after directive"),
+    /// E0301: A `.dat` line is not `VALUE` or `VALUE * COUNT`.
+    InvalidDataLine => ("E0301", "expected VALUE or VALUE * COUNT"),
+    /// E0302: A `.dat` repetition count is not a positive number.
+    InvalidDataCount => ("E0302", "repetition count must be a positive number"),
+    /// E0303: A `.dat` number is outside -9999 to 9999.
+    DataNumberOutOfRange => ("E0303", "number must be between -9999 and 9999"),
+    /// E0304: A `.dat` file holds more values than a file can.
+    DataFileTooLong => ("E0304", "file has more than 9999 values"),
+    /// W0001: A NOOP only spends a cycle.
+    NoOpHint => ("W0001", "NOOP only spends a cycle"),
+    /// W0002: A COPY from a register to itself.
+    RedundantCopyHint => ("W0002", "COPY to the same register has no effect"),
+    /// W0003: A jump to the instruction that would run next anyway.
+    JumpToNextLineHint => ("W0003", "jump goes to the next instruction anyway"),
+    /// W0004: A TEST whose result is overwritten before it is read.
+    UnusedTestHint => ("W0004", "TEST result is overwritten before it is used"),
+    /// W0005: An instruction that could be moved before its loop.
+    LoopInvariantHint => ("W0005", "instruction could be moved before its loop"),
+}
+
+impl DiagnosticCode {
+    /// Returns the stable code, e.g. "E0104".
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use exapunks_lite::diagnostic::DiagnosticCode;
+    /// assert_eq!(DiagnosticCode::InvalidTestOperation.code(), "E0104");
+    /// ```
+    #[must_use]
+    pub fn code(self) -> &'static str {
+        self.entry().0
+    }
+
+    /// Returns the default English message.
+    #[must_use]
+    pub fn message(self) -> &'static str {
+        self.entry().1
+    }
+}
+
+impl fmt::Display for DiagnosticCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.message())
+    }
+}
+
+/// Implemented by every error or hint that can be shown to a user.
+pub trait Diagnostic {
+    /// Returns the stable code identifying this error or hint.
+    fn diagnostic_code(&self) -> DiagnosticCode;
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::DiagnosticCode;
+
+    #[test]
+    fn test_codes_are_unique() {
+        let codes: HashSet<&str> = DiagnosticCode::ALL.iter().map(|code| code.code()).collect();
+
+        assert_eq!(codes.len(), DiagnosticCode::ALL.len());
+    }
+
+    #[test]
+    fn test_codes_are_well_formed() {
+        for code in DiagnosticCode::ALL {
+            let code = code.code();
+
+            assert_eq!(code.len(), 5);
+            assert!(code.starts_with('E') || code.starts_with('W'));
+            assert!(code[1..]
+                .chars()
+                .all(|character| character.is_ascii_digit()));
+        }
+    }
+}
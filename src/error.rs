@@ -0,0 +1,193 @@
+use std::error::Error;
+use std::fmt;
+
+use crate::data_file;
+use crate::diagnostic::{Diagnostic, DiagnosticCode};
+use crate::instruction;
+use crate::preprocessor;
+use crate::value;
+
+/// The top-level error for the crate.
+///
+/// Every module keeps its own error type, and each converts into an `ExapunksError` with `?`, so
+/// embedders only need to handle one type. The original error is available through
+/// [`Error::source`], and its stable code through [`Diagnostic::diagnostic_code`].
+///
+/// `Display` writes the code followed by the original error's message, e.g.
+/// `error[E0102]: wrong number of operands for instruction`.
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub enum ExapunksError {
+    /// A [`Value`](crate::value::Value) failed to parse.
+    ValueParse(value::ParseError),
+    /// An [`Instruction`](crate::instruction::Instruction) failed to parse.
+    InstructionParse(instruction::ParseError),
+    /// A line of a program failed to parse as an [`Instruction`](crate::instruction::Instruction).
+    LineParse(instruction::LineParseError),
+    /// A non-number [`Value`](crate::value::Value) was used where a number was needed.
+    NotANumber(value::NotANumberError),
+    /// DIVI or MODI was given a divisor of 0.
+    DivideByZero(value::DivideByZeroError),
+    /// Conditional assembly directives failed to resolve.
+    Preprocess(preprocessor::PreprocessError),
+    /// A `.dat` file failed to parse.
+    DataFile(data_file::DataFileError),
+}
+
+impl Diagnostic for ExapunksError {
+    fn diagnostic_code(&self) -> DiagnosticCode {
+        match self {
+            Self::ValueParse(error) => error.diagnostic_code(),
+            Self::InstructionParse(error) => error.diagnostic_code(),
+            Self::LineParse(error) => error.diagnostic_code(),
+            Self::NotANumber(error) => error.diagnostic_code(),
+            Self::DivideByZero(error) => error.diagnostic_code(),
+            Self::Preprocess(error) => error.diagnostic_code(),
+            Self::DataFile(error) => error.diagnostic_code(),
+        }
+    }
+}
+
+impl fmt::Display for ExapunksError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let error: &dyn fmt::Display = match self {
+            Self::ValueParse(error) => error,
+            Self::InstructionParse(error) => error,
+            Self::LineParse(error) => error,
+            Self::NotANumber(error) => error,
+            Self::DivideByZero(error) => error,
+            Self::Preprocess(error) => error,
+            Self::DataFile(error) => error,
+        };
+
+        write!(f, "error[{}]: {error}", self.diagnostic_code().code())
+    }
+}
+
+impl Error for ExapunksError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::ValueParse(error) => Some(error),
+            Self::InstructionParse(error) => Some(error),
+            Self::LineParse(error) => Some(error),
+            Self::NotANumber(error) => Some(error),
+            Self::DivideByZero(error) => Some(error),
+            Self::Preprocess(error) => Some(error),
+            Self::DataFile(error) => Some(error),
+        }
+    }
+}
+
+impl From<value::ParseError> for ExapunksError {
+    fn from(error: value::ParseError) -> Self {
+        ExapunksError::ValueParse(error)
+    }
+}
+
+impl From<instruction::ParseError> for ExapunksError {
+    fn from(error: instruction::ParseError) -> Self {
+        ExapunksError::InstructionParse(error)
+    }
+}
+
+impl From<instruction::LineParseError> for ExapunksError {
+    fn from(error: instruction::LineParseError) -> Self {
+        ExapunksError::LineParse(error)
+    }
+}
+
+impl From<value::NotANumberError> for ExapunksError {
+    fn from(error: value::NotANumberError) -> Self {
+        ExapunksError::NotANumber(error)
+    }
+}
+
+impl From<value::DivideByZeroError> for ExapunksError {
+    fn from(error: value::DivideByZeroError) -> Self {
+        ExapunksError::DivideByZero(error)
+    }
+}
+
+impl From<preprocessor::PreprocessError> for ExapunksError {
+    fn from(error: preprocessor::PreprocessError) -> Self {
+        ExapunksError::Preprocess(error)
+    }
+}
+
+impl From<data_file::DataFileError> for ExapunksError {
+    fn from(error: data_file::DataFileError) -> Self {
+        ExapunksError::DataFile(error)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::error::Error;
+
+    use super::ExapunksError;
+    use crate::diagnostic::{Diagnostic, DiagnosticCode};
+    use crate::instruction::{self, Instruction};
+    use crate::preprocessor::PreprocessError;
+    use crate::value::{self, Value};
+
+    fn parse_both(instruction: &str, value: &str) -> Result<(Instruction, Value), ExapunksError> {
+        Ok((instruction.parse()?, value.parse()?))
+    }
+
+    #[test]
+    fn test_from_instruction_parse_error() {
+        let expected = Err(ExapunksError::InstructionParse(
+            instruction::ParseError::InvalidLineLength,
+        ));
+
+        let result = parse_both("COPY X", "1");
+
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn test_from_value_parse_error() {
+        let expected = Err(ExapunksError::ValueParse(value::ParseError));
+
+        let result = parse_both("HALT", "");
+
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn test_from_not_a_number_error() {
+        let keyword = Value::Keyword("KEYWORD".into());
+
+        let expected = Err(ExapunksError::NotANumber(value::NotANumberError(
+            keyword.clone(),
+        )));
+
+        let result: Result<isize, ExapunksError> = isize::try_from(keyword).map_err(Into::into);
+
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn test_display_and_source() {
+        let error = ExapunksError::from(instruction::ParseError::InvalidTestOperation);
+
+        assert_eq!(
+            error.to_string(),
+            "error[E0104]: TEST operation must be '=', '>', or '<'"
+        );
+        assert_eq!(
+            error.source().map(ToString::to_string),
+            Some("TEST operation must be '=', '>', or '<'".to_string())
+        );
+    }
+
+    #[test]
+    fn test_diagnostic_code() {
+        let error = ExapunksError::from(PreprocessError::UnterminatedIf(4));
+
+        assert_eq!(error.diagnostic_code(), DiagnosticCode::UnterminatedIf);
+        assert_eq!(
+            error.to_string(),
+            "error[E0205]: line 4: @IF is never closed by @END"
+        );
+    }
+}
@@ -1,10 +1,54 @@
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
 /// This enum dictates which communication mode the [`Exa`] is in.
 ///
 /// * Global - The "M" register can be written/read by all other EXAs also in Global mode.
 /// * Local - The "M" register can be written/read by all other EXAs in the same [`Host`] that are
-/// also in Local mode.
+///   also in Local mode.
 #[derive(Debug, Clone, Copy, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum CommunicationMode {
     Global,
     Local,
 }
+
+impl CommunicationMode {
+    /// Returns the other mode, which is what the MODE instruction switches an [`Exa`] to.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use exapunks_lite::exa::CommunicationMode;
+    /// assert_eq!(CommunicationMode::Global.toggled(), CommunicationMode::Local);
+    /// assert_eq!(CommunicationMode::Local.toggled(), CommunicationMode::Global);
+    /// ```
+    #[must_use]
+    pub fn toggled(self) -> Self {
+        match self {
+            Self::Global => Self::Local,
+            Self::Local => Self::Global,
+        }
+    }
+}
+
+impl Default for CommunicationMode {
+    /// Every [`Exa`] starts in Global mode.
+    fn default() -> Self {
+        Self::Global
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::CommunicationMode;
+
+    #[test]
+    fn test_toggled() {
+        let mode = CommunicationMode::default();
+
+        assert_eq!(mode, CommunicationMode::Global);
+        assert_eq!(mode.toggled(), CommunicationMode::Local);
+        assert_eq!(mode.toggled().toggled(), mode);
+    }
+}
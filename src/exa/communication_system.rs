@@ -0,0 +1,236 @@
+use std::collections::{HashSet, VecDeque};
+
+use crate::value::Value;
+
+/// The shared M register used by [`Exa`]s in Global mode.
+///
+/// M has no storage of its own: a write blocks the writer until some other EXA reads the value,
+/// and a read blocks the reader until some other EXA offers one. Each write is handed to exactly
+/// one reader, oldest write first.
+///
+/// An EXA runs at most one instruction per cycle, so each reader can take at most one value per
+/// cycle. Call [`CommunicationSystem::end_cycle`] once every EXA has run to allow the next reads.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CommunicationSystem {
+    pending_writes: VecDeque<(String, Value)>,
+    readers_this_cycle: HashSet<String>,
+}
+
+impl CommunicationSystem {
+    /// Creates a system with no pending writes.
+    #[must_use]
+    pub fn new() -> Self {
+        CommunicationSystem::default()
+    }
+
+    /// Offers a value on M from the given EXA.
+    ///
+    /// The writer stays blocked until [`CommunicationSystem::is_waiting_to_write`] returns false.
+    /// Returns false, and drops the value, if the writer is already blocked on an earlier write.
+    pub fn write(&mut self, writer_id: &str, value: Value) -> bool {
+        if self.is_waiting_to_write(writer_id) {
+            return false;
+        }
+
+        self.pending_writes
+            .push_back((writer_id.to_string(), value));
+
+        true
+    }
+
+    /// Takes the oldest pending write from an EXA other than the reader, returning the writer's
+    /// id and the value.
+    ///
+    /// Returns `None` if no other EXA is waiting to write, or if the reader already took a value
+    /// this cycle. In either case the reader should block and try again next cycle.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use exapunks_lite::exa::CommunicationSystem;
+    /// # use exapunks_lite::value::Value;
+    /// let mut system = CommunicationSystem::new();
+    ///
+    /// assert_eq!(system.read("XB"), None);
+    ///
+    /// assert!(system.write("XA", Value::Number(5)));
+    ///
+    /// assert_eq!(system.read("XA"), None);
+    /// assert!(system.is_waiting_to_write("XA"));
+    /// assert_eq!(system.read("XB"), Some(("XA".to_string(), Value::Number(5))));
+    /// assert!(!system.is_waiting_to_write("XA"));
+    /// ```
+    pub fn read(&mut self, reader_id: &str) -> Option<(String, Value)> {
+        if self.readers_this_cycle.contains(reader_id) {
+            return None;
+        }
+
+        let index = self
+            .pending_writes
+            .iter()
+            .position(|(writer_id, _)| writer_id != reader_id)?;
+
+        self.readers_this_cycle.insert(reader_id.to_string());
+
+        self.pending_writes.remove(index)
+    }
+
+    /// Lets every reader take a value again. Call this once all EXAs have run for the cycle.
+    pub fn end_cycle(&mut self) {
+        self.readers_this_cycle.clear();
+    }
+
+    /// Returns true if the given EXA has written a value that hasn't been read yet.
+    #[must_use]
+    pub fn is_waiting_to_write(&self, writer_id: &str) -> bool {
+        self.pending_writes
+            .iter()
+            .any(|(pending_writer_id, _)| pending_writer_id == writer_id)
+    }
+
+    /// Returns true if a value is waiting to be read, which is what TEST MRD checks.
+    #[must_use]
+    pub fn has_pending_write(&self) -> bool {
+        !self.pending_writes.is_empty()
+    }
+
+    /// Consumes and discards the oldest pending write, which is what VOID M does.
+    ///
+    /// Returns the id of the writer to unblock, or `None` if nothing is waiting, in which case
+    /// the EXA running VOID M should block like a read.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use exapunks_lite::exa::CommunicationSystem;
+    /// # use exapunks_lite::value::Value;
+    /// let mut system = CommunicationSystem::new();
+    ///
+    /// assert!(system.write("XA", Value::Number(5)));
+    ///
+    /// assert!(system.has_pending_write());
+    /// assert_eq!(system.void("XB"), Some("XA".to_string()));
+    /// assert!(!system.has_pending_write());
+    /// ```
+    pub fn void(&mut self, reader_id: &str) -> Option<String> {
+        self.read(reader_id).map(|(writer_id, _)| writer_id)
+    }
+
+    /// Drops any pending write from the given EXA, e.g. because it was killed.
+    pub fn cancel(&mut self, writer_id: &str) {
+        self.pending_writes
+            .retain(|(pending_writer_id, _)| pending_writer_id != writer_id);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::CommunicationSystem;
+    use crate::value::Value;
+
+    #[test]
+    fn test_reads_pair_with_writes_in_order() {
+        let mut system = CommunicationSystem::new();
+
+        assert!(system.write("XA", Value::Number(1)));
+        assert!(system.write("XB", Value::Keyword("TWO".into())));
+
+        assert_eq!(
+            system.read("XC"),
+            Some(("XA".to_string(), Value::Number(1)))
+        );
+        assert!(!system.is_waiting_to_write("XA"));
+        assert!(system.is_waiting_to_write("XB"));
+        assert_eq!(
+            system.read("XD"),
+            Some(("XB".to_string(), Value::Keyword("TWO".into())))
+        );
+        assert_eq!(system.read("XE"), None);
+    }
+
+    #[test]
+    fn test_write_while_blocked() {
+        let mut system = CommunicationSystem::new();
+
+        assert!(system.write("XA", Value::Number(1)));
+        assert!(!system.write("XA", Value::Number(2)));
+
+        assert_eq!(
+            system.read("XB"),
+            Some(("XA".to_string(), Value::Number(1)))
+        );
+        assert!(!system.has_pending_write());
+    }
+
+    #[test]
+    fn test_reader_skips_its_own_write() {
+        let mut system = CommunicationSystem::new();
+
+        assert!(system.write("XA", Value::Number(1)));
+
+        assert_eq!(system.read("XA"), None);
+        assert!(system.write("XB", Value::Number(2)));
+        assert_eq!(
+            system.read("XA"),
+            Some(("XB".to_string(), Value::Number(2)))
+        );
+        assert!(system.is_waiting_to_write("XA"));
+    }
+
+    #[test]
+    fn test_one_read_per_reader_per_cycle() {
+        let mut system = CommunicationSystem::new();
+
+        assert!(system.write("XA", Value::Number(1)));
+        assert!(system.write("XB", Value::Number(2)));
+
+        assert_eq!(
+            system.read("XC"),
+            Some(("XA".to_string(), Value::Number(1)))
+        );
+        assert_eq!(system.read("XC"), None);
+        assert!(system.is_waiting_to_write("XB"));
+
+        system.end_cycle();
+
+        assert_eq!(
+            system.read("XC"),
+            Some(("XB".to_string(), Value::Number(2)))
+        );
+    }
+
+    #[test]
+    fn test_has_pending_write_and_void() {
+        let mut system = CommunicationSystem::new();
+
+        assert!(!system.has_pending_write());
+        assert_eq!(system.void("XC"), None);
+
+        assert!(system.write("XA", Value::Number(1)));
+        assert!(system.write("XB", Value::Number(2)));
+
+        assert!(system.has_pending_write());
+        assert_eq!(system.void("XC"), Some("XA".to_string()));
+        assert!(system.has_pending_write());
+        assert_eq!(
+            system.read("XD"),
+            Some(("XB".to_string(), Value::Number(2)))
+        );
+        assert!(!system.has_pending_write());
+    }
+
+    #[test]
+    fn test_cancel() {
+        let mut system = CommunicationSystem::new();
+
+        assert!(system.write("XA", Value::Number(1)));
+        assert!(system.write("XB", Value::Number(2)));
+        system.cancel("XA");
+
+        assert!(!system.is_waiting_to_write("XA"));
+        assert_eq!(
+            system.read("XC"),
+            Some(("XB".to_string(), Value::Number(2)))
+        );
+    }
+}
@@ -0,0 +1,182 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::naming::name_from_index;
+
+/// The prefix the game gives to player EXAs.
+const DEFAULT_PREFIX: &str = "X";
+
+/// The letters used after the prefix, in the order they are handed out.
+const LETTERS: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ";
+
+/// Hands out unique [`Exa`] ids using the game's naming scheme.
+///
+/// * New EXAs are named with a prefix followed by letters: XA, XB, ..., XZ, XAA, XAB, ...
+/// * Replicas are named after their parent with a `:n` suffix: XA:0, XA:1, XA:0:0, ...
+///
+/// Each prefix counts independently, so teams in battle mode can have their own (e.g. "X" and
+/// "Y"). Every id handed out, or reserved by hand, is remembered, and is never handed out again
+/// by any prefix.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ExaIdAllocator {
+    next_index_by_prefix: HashMap<String, usize>,
+    next_replica_by_parent: HashMap<String, usize>,
+    allocated: HashSet<String>,
+}
+
+impl ExaIdAllocator {
+    /// Creates an allocator with no ids handed out.
+    #[must_use]
+    pub fn new() -> Self {
+        ExaIdAllocator::default()
+    }
+
+    /// Returns the next id using the default "X" prefix.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use exapunks_lite::exa::ExaIdAllocator;
+    /// let mut allocator = ExaIdAllocator::new();
+    ///
+    /// assert_eq!(allocator.next_id(), "XA");
+    /// assert_eq!(allocator.next_id(), "XB");
+    /// ```
+    pub fn next_id(&mut self) -> String {
+        self.next_id_with_prefix(DEFAULT_PREFIX)
+    }
+
+    /// Returns the next id for the given prefix.
+    ///
+    /// Ids that are already taken are skipped.
+    pub fn next_id_with_prefix(&mut self, prefix: &str) -> String {
+        loop {
+            let index = self
+                .next_index_by_prefix
+                .entry(prefix.to_string())
+                .or_insert(0);
+            let id = format!("{prefix}{}", name_from_index(*index, LETTERS));
+
+            *index += 1;
+
+            if self.allocated.insert(id.clone()) {
+                return id;
+            }
+        }
+    }
+
+    /// Returns the next id for a replica of the given parent EXA.
+    ///
+    /// Ids that are already taken are skipped.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use exapunks_lite::exa::ExaIdAllocator;
+    /// let mut allocator = ExaIdAllocator::new();
+    ///
+    /// let parent = allocator.next_id();
+    /// let replica = allocator.next_replicated_exa_id(&parent);
+    ///
+    /// assert_eq!(replica, "XA:0");
+    /// assert_eq!(allocator.next_replicated_exa_id(&replica), "XA:0:0");
+    /// assert_eq!(allocator.next_replicated_exa_id(&parent), "XA:1");
+    /// ```
+    pub fn next_replicated_exa_id(&mut self, parent_id: &str) -> String {
+        loop {
+            let replica = self
+                .next_replica_by_parent
+                .entry(parent_id.to_string())
+                .or_insert(0);
+            let id = format!("{parent_id}:{replica}");
+
+            *replica += 1;
+
+            if self.allocated.insert(id.clone()) {
+                return id;
+            }
+        }
+    }
+
+    /// Marks a hand-picked id as taken, so it is never handed out.
+    ///
+    /// Returns false if the id was already taken.
+    pub fn reserve(&mut self, id: &str) -> bool {
+        self.allocated.insert(id.to_string())
+    }
+
+    /// Returns true if the id has been handed out or reserved.
+    #[must_use]
+    pub fn is_allocated(&self, id: &str) -> bool {
+        self.allocated.contains(id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ExaIdAllocator;
+
+    #[test]
+    fn test_next_id() {
+        let mut allocator = ExaIdAllocator::new();
+
+        let ids: Vec<String> = (0..28).map(|_| allocator.next_id()).collect();
+
+        assert_eq!(ids[0], "XA");
+        assert_eq!(ids[1], "XB");
+        assert_eq!(ids[25], "XZ");
+        assert_eq!(ids[26], "XAA");
+        assert_eq!(ids[27], "XAB");
+    }
+
+    #[test]
+    fn test_next_id_with_prefix() {
+        let mut allocator = ExaIdAllocator::new();
+
+        let x_id = allocator.next_id();
+        let y_id = allocator.next_id_with_prefix("Y");
+        let second_y_id = allocator.next_id_with_prefix("Y");
+
+        assert_eq!(x_id, "XA");
+        assert_eq!(y_id, "YA");
+        assert_eq!(second_y_id, "YB");
+    }
+
+    #[test]
+    fn test_next_id_skips_ids_from_other_prefixes() {
+        let mut allocator = ExaIdAllocator::new();
+
+        let xa_id = allocator.next_id_with_prefix("XA");
+        let ids: Vec<String> = (0..27).map(|_| allocator.next_id()).collect();
+
+        assert_eq!(xa_id, "XAA");
+        assert_eq!(ids[26], "XAB");
+    }
+
+    #[test]
+    fn test_next_replicated_exa_id() {
+        let mut allocator = ExaIdAllocator::new();
+
+        let parent = allocator.next_id();
+        let first_replica = allocator.next_replicated_exa_id(&parent);
+        let second_replica = allocator.next_replicated_exa_id(&parent);
+        let nested_replica = allocator.next_replicated_exa_id(&first_replica);
+
+        assert_eq!(first_replica, "XA:0");
+        assert_eq!(second_replica, "XA:1");
+        assert_eq!(nested_replica, "XA:0:0");
+    }
+
+    #[test]
+    fn test_reserve() {
+        let mut allocator = ExaIdAllocator::new();
+
+        assert!(allocator.reserve("XA"));
+        assert!(allocator.reserve("XA:0"));
+        assert!(!allocator.reserve("XA"));
+
+        assert_eq!(allocator.next_id(), "XB");
+        assert_eq!(allocator.next_replicated_exa_id("XA"), "XA:1");
+        assert!(allocator.is_allocated("XB"));
+        assert!(!allocator.is_allocated("XC"));
+    }
+}
@@ -1,2 +1,7 @@
-#[allow(dead_code)]
-mod communication_mode;
+pub mod communication_mode;
+pub mod communication_system;
+pub mod id_allocator;
+
+pub use communication_mode::CommunicationMode;
+pub use communication_system::CommunicationSystem;
+pub use id_allocator::ExaIdAllocator;
@@ -1,5 +1,11 @@
+use std::error::Error;
+use std::fmt;
 use std::str::FromStr;
 
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::diagnostic::{Diagnostic, DiagnosticCode};
 use crate::value::Value;
 
 /// An instruction describes a command for an [`Exa`] to execute.
@@ -7,6 +13,7 @@ use crate::value::Value;
 /// Instructions are comprised of [`Value`]s which tell the [`Exa`] how to extract the information
 /// to execute.
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum Instruction {
     Copy(Value, Value),
     Add(Value, Value, Value),
@@ -43,7 +50,7 @@ pub enum Instruction {
     Random(Value, Value, Value),
 }
 
-/// A dummy struct to indicate that there was an error on the [`FromStr`] implementation.
+/// The reasons a line can fail to parse in the [`FromStr`] implementation.
 #[derive(Debug, Eq, PartialEq, Clone)]
 pub enum ParseError {
     InvalidInstruction,
@@ -53,7 +60,78 @@ pub enum ParseError {
     MissingTestOperation,
 }
 
+impl Diagnostic for ParseError {
+    fn diagnostic_code(&self) -> DiagnosticCode {
+        match self {
+            Self::InvalidInstruction => DiagnosticCode::InvalidInstruction,
+            Self::InvalidLineLength => DiagnosticCode::InvalidLineLength,
+            Self::InvalidValues => DiagnosticCode::InvalidOperand,
+            Self::InvalidTestOperation => DiagnosticCode::InvalidTestOperation,
+            Self::MissingTestOperation => DiagnosticCode::MissingTestOperation,
+        }
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.diagnostic_code())
+    }
+}
+
+impl Error for ParseError {}
+
+/// A [`ParseError`] with the 1-based line number of the offending line in a program.
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct LineParseError {
+    /// The 1-based line number.
+    pub line: usize,
+    /// Why the line failed to parse.
+    pub error: ParseError,
+}
+
+impl Diagnostic for LineParseError {
+    fn diagnostic_code(&self) -> DiagnosticCode {
+        self.error.diagnostic_code()
+    }
+}
+
+impl fmt::Display for LineParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.error)
+    }
+}
+
+impl Error for LineParseError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        Some(&self.error)
+    }
+}
+
 impl Instruction {
+    /// Splits the operands following the instruction in a given line.
+    ///
+    /// The line is split on single spaces, so repeated or trailing spaces produce empty operands
+    /// rather than being skipped. The operands are borrowed from the line, so nothing is allocated
+    /// until a [`Value`] is built from them.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the line does not have exactly `N` operands after the instruction.
+    fn split_operands<const N: usize>(line: &str) -> Result<[&str; N], ParseError> {
+        let mut operands = [""; N];
+        let mut words = line.split(' ').skip(1);
+
+        for operand in &mut operands {
+            *operand = words.next().ok_or(ParseError::InvalidLineLength)?;
+        }
+
+        if words.next().is_some() {
+            return Err(ParseError::InvalidLineLength);
+        }
+
+        Ok(operands)
+    }
+
     /// Parses a given line to a `RegisterId`/`Number`.
     ///
     /// A valid line is "[instruction] [first value]".
@@ -68,13 +146,9 @@ impl Instruction {
     /// * Is not 2 distinct words seperated by a space.
     /// * Doesn't have a valid register id and/or number as the first value.
     fn parse_rn(line: &str) -> Result<Value, ParseError> {
-        let split_line: Vec<&str> = line.split(' ').collect();
-
-        if split_line.len() != 2 {
-            return Err(ParseError::InvalidLineLength);
-        }
+        let [source] = Self::split_operands(line)?;
 
-        Value::new_number_or_register_id(split_line[1]).map_err(|_| ParseError::InvalidValues)
+        Value::new_number_or_register_id(source).map_err(|_| ParseError::InvalidValues)
     }
 
     /// Parses a given line to a (`RegisterId`/`Number`, `RegisterId`) tuple.
@@ -93,14 +167,10 @@ impl Instruction {
     /// * Doesn't have a valid register id and/or number as the first value.
     /// * Doesn't have a valid register id as the second value.
     fn parse_rn_r(line: &str) -> Result<(Value, Value), ParseError> {
-        let split_line: Vec<&str> = line.split(' ').collect();
+        let [source, destination] = Self::split_operands(line)?;
 
-        if split_line.len() != 3 {
-            return Err(ParseError::InvalidLineLength);
-        }
-
-        let source_result = Value::new_number_or_register_id(split_line[1]);
-        let destination_result = Value::new_register_id(split_line[2]);
+        let source_result = Value::new_number_or_register_id(source);
+        let destination_result = Value::new_register_id(destination);
 
         match (source_result, destination_result) {
             (Ok(source), Ok(destination)) => Ok((source, destination)),
@@ -126,15 +196,11 @@ impl Instruction {
     /// * Doesn't have a valid register id and/or number as the second value.
     /// * Doesn't have a valid register id as the third value.
     fn parse_rn_rn_r(line: &str) -> Result<(Value, Value, Value), ParseError> {
-        let split_line: Vec<&str> = line.split(' ').collect();
-
-        if split_line.len() != 4 {
-            return Err(ParseError::InvalidLineLength);
-        }
+        let [first_source, second_source, destination] = Self::split_operands(line)?;
 
-        let first_result = Value::new_number_or_register_id(split_line[1]);
-        let second_result = Value::new_number_or_register_id(split_line[2]);
-        let destination_result = Value::new_register_id(split_line[3]);
+        let first_result = Value::new_number_or_register_id(first_source);
+        let second_result = Value::new_number_or_register_id(second_source);
+        let destination_result = Value::new_register_id(destination);
 
         match (first_result, second_result, destination_result) {
             (Ok(first_source), Ok(second_source), Ok(destination)) => {
@@ -158,13 +224,9 @@ impl Instruction {
     /// * Is not 2 distinct words seperated by a space.
     /// * Doesn't have a valid register id as the first value.
     fn parse_r(line: &str) -> Result<Value, ParseError> {
-        let split_line: Vec<&str> = line.split(' ').collect();
-
-        if split_line.len() != 2 {
-            return Err(ParseError::InvalidLineLength);
-        }
+        let [register_id] = Self::split_operands(line)?;
 
-        Value::new_register_id(split_line[1]).map_err(|_| ParseError::InvalidValues)
+        Value::new_register_id(register_id).map_err(|_| ParseError::InvalidValues)
     }
 
     /// Parses a given line to a `LabelId`.
@@ -181,13 +243,9 @@ impl Instruction {
     /// * Is not 2 distinct words seperated by a space.
     /// * Doesn't have a valid label id as the first value.
     fn parse_l(line: &str) -> Result<Value, ParseError> {
-        let split_line: Vec<&str> = line.split(' ').collect();
-
-        if split_line.len() != 2 {
-            return Err(ParseError::InvalidLineLength);
-        }
+        let [label_id] = Self::split_operands(line)?;
 
-        Value::new_label_id(split_line[1]).map_err(|_| ParseError::InvalidValues)
+        Value::new_label_id(label_id).map_err(|_| ParseError::InvalidValues)
     }
 
     /// Parses a given test line to an instruction.
@@ -207,25 +265,23 @@ impl Instruction {
     /// * Doesn't have a valid register id and/or number as the second value.
     /// * Doesn't have a valid operation (i.e. '=', '>', or '<').
     fn parse_test(line: &str) -> Result<Instruction, ParseError> {
-        let split_line: Vec<&str> = line.split(' ').collect();
+        let [first_source, operation, second_source] = Self::split_operands(line)?;
 
-        if split_line.len() != 4 {
-            return Err(ParseError::InvalidLineLength);
-        } else if !matches!(split_line[2], "=" | ">" | "<") {
+        if !matches!(operation, "=" | ">" | "<") {
             return Err(ParseError::InvalidTestOperation);
         }
 
-        let first_source_result = Value::new_number_or_register_id(split_line[1]);
-        let second_source_result = Value::new_number_or_register_id(split_line[3]);
+        let first_source_result = Value::new_number_or_register_id(first_source);
+        let second_source_result = Value::new_number_or_register_id(second_source);
 
         match (first_source_result, second_source_result) {
-            (Ok(first_source), Ok(second_source)) if (split_line[2] == "=") => {
+            (Ok(first_source), Ok(second_source)) if (operation == "=") => {
                 Ok(Self::TestEqual(first_source, second_source))
             }
-            (Ok(first_source), Ok(second_source)) if (split_line[2] == ">") => {
+            (Ok(first_source), Ok(second_source)) if (operation == ">") => {
                 Ok(Self::TestGreaterThan(first_source, second_source))
             }
-            (Ok(first_source), Ok(second_source)) if (split_line[2] == "<") => {
+            (Ok(first_source), Ok(second_source)) if (operation == "<") => {
                 Ok(Self::TestLessThan(first_source, second_source))
             }
             _ => Err(ParseError::InvalidValues),
@@ -253,6 +309,48 @@ impl Instruction {
     }
 }
 
+impl fmt::Display for Instruction {
+    /// Writes the instruction in the same form [`FromStr`] accepts, so that parsing the output
+    /// gives back an equal instruction. A [`Instruction::Note`] is written without its comment.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Copy(src, dest) => write!(f, "COPY {src} {dest}"),
+            Self::Add(src1, src2, dest) => write!(f, "ADDI {src1} {src2} {dest}"),
+            Self::Subtract(src1, src2, dest) => write!(f, "SUBI {src1} {src2} {dest}"),
+            Self::Multiply(src1, src2, dest) => write!(f, "MULI {src1} {src2} {dest}"),
+            Self::Divide(src1, src2, dest) => write!(f, "DIVI {src1} {src2} {dest}"),
+            Self::Modulo(src1, src2, dest) => write!(f, "MODI {src1} {src2} {dest}"),
+            Self::Swiz(src1, src2, dest) => write!(f, "SWIZ {src1} {src2} {dest}"),
+            Self::Mark(label) => write!(f, "MARK {label}"),
+            Self::Jump(label) => write!(f, "JUMP {label}"),
+            Self::JumpIfTrue(label) => write!(f, "TJMP {label}"),
+            Self::JumpIfFalse(label) => write!(f, "FJMP {label}"),
+            Self::TestEqual(src1, src2) => write!(f, "TEST {src1} = {src2}"),
+            Self::TestGreaterThan(src1, src2) => write!(f, "TEST {src1} > {src2}"),
+            Self::TestLessThan(src1, src2) => write!(f, "TEST {src1} < {src2}"),
+            Self::Replicate(label) => write!(f, "REPL {label}"),
+            Self::Halt => write!(f, "HALT"),
+            Self::Kill => write!(f, "KILL"),
+            Self::Link(src) => write!(f, "LINK {src}"),
+            Self::Host(dest) => write!(f, "HOST {dest}"),
+            Self::Mode => write!(f, "MODE"),
+            Self::VoidM => write!(f, "VOID M"),
+            Self::TestMRD => write!(f, "TEST MRD"),
+            Self::Make => write!(f, "MAKE"),
+            Self::Grab(src) => write!(f, "GRAB {src}"),
+            Self::File(dest) => write!(f, "FILE {dest}"),
+            Self::Seek(src) => write!(f, "SEEK {src}"),
+            Self::VoidF => write!(f, "VOID F"),
+            Self::Drop => write!(f, "DROP"),
+            Self::Wipe => write!(f, "WIPE"),
+            Self::TestEndOfFile => write!(f, "TEST EOF"),
+            Self::Note => write!(f, "NOTE"),
+            Self::NoOp => write!(f, "NOOP"),
+            Self::Random(src1, src2, dest) => write!(f, "RAND {src1} {src2} {dest}"),
+        }
+    }
+}
+
 impl FromStr for Instruction {
     type Err = ParseError;
 
@@ -342,19 +440,19 @@ mod tests {
 
         let expected1 = Ok(Instruction::Copy(
             Value::Number(-9999),
-            Value::RegisterId("X".to_string()),
+            Value::RegisterId("X".into()),
         ));
         let expected2 = Ok(Instruction::Copy(
-            Value::RegisterId("T".to_string()),
-            Value::RegisterId("X".to_string()),
+            Value::RegisterId("T".into()),
+            Value::RegisterId("X".into()),
         ));
         let expected3 = Ok(Instruction::Copy(
             Value::Number(666),
-            Value::RegisterId("#NERV".to_string()),
+            Value::RegisterId("#NERV".into()),
         ));
         let expected4 = Ok(Instruction::Copy(
-            Value::RegisterId("#NERV".to_string()),
-            Value::RegisterId("X".to_string()),
+            Value::RegisterId("#NERV".into()),
+            Value::RegisterId("X".into()),
         ));
         let expected_err1: Result<Instruction, ParseError> = Err(ParseError::InvalidValues);
         let expected_err2: Result<Instruction, ParseError> = Err(ParseError::InvalidInstruction);
@@ -397,23 +495,23 @@ mod tests {
 
         let expected1 = Ok(Instruction::Add(
             Value::Number(-9999),
-            Value::RegisterId("X".to_string()),
-            Value::RegisterId("X".to_string()),
+            Value::RegisterId("X".into()),
+            Value::RegisterId("X".into()),
         ));
         let expected2 = Ok(Instruction::Add(
-            Value::RegisterId("T".to_string()),
-            Value::RegisterId("X".to_string()),
-            Value::RegisterId("#NERV".to_string()),
+            Value::RegisterId("T".into()),
+            Value::RegisterId("X".into()),
+            Value::RegisterId("#NERV".into()),
         ));
         let expected3 = Ok(Instruction::Add(
             Value::Number(666),
             Value::Number(1),
-            Value::RegisterId("#NERV".to_string()),
+            Value::RegisterId("#NERV".into()),
         ));
         let expected4 = Ok(Instruction::Add(
-            Value::RegisterId("#NERV".to_string()),
+            Value::RegisterId("#NERV".into()),
             Value::Number(-666),
-            Value::RegisterId("X".to_string()),
+            Value::RegisterId("X".into()),
         ));
         let expected_err1: Result<Instruction, ParseError> = Err(ParseError::InvalidValues);
         let expected_err2: Result<Instruction, ParseError> = Err(ParseError::InvalidInstruction);
@@ -453,10 +551,10 @@ mod tests {
         let invalid_instruction3 = "MARK";
         let invalid_instruction4 = "MARK ";
 
-        let expected1 = Ok(Instruction::Mark(Value::LabelId("LABEL".to_string())));
-        let expected2 = Ok(Instruction::Mark(Value::LabelId("-666".to_string())));
-        let expected3 = Ok(Instruction::Mark(Value::LabelId("#NERV".to_string())));
-        let expected4 = Ok(Instruction::Mark(Value::LabelId("666".to_string())));
+        let expected1 = Ok(Instruction::Mark(Value::LabelId("LABEL".into())));
+        let expected2 = Ok(Instruction::Mark(Value::LabelId("-666".into())));
+        let expected3 = Ok(Instruction::Mark(Value::LabelId("#NERV".into())));
+        let expected4 = Ok(Instruction::Mark(Value::LabelId("666".into())));
         let expected_err1: Result<Instruction, ParseError> = Err(ParseError::InvalidLineLength);
         let expected_err2: Result<Instruction, ParseError> = Err(ParseError::InvalidInstruction);
         let expected_err3: Result<Instruction, ParseError> = Err(ParseError::InvalidLineLength);
@@ -498,14 +596,14 @@ mod tests {
 
         let expected1 = Ok(Instruction::TestEqual(
             Value::Number(-9999),
-            Value::RegisterId("X".to_string()),
+            Value::RegisterId("X".into()),
         ));
         let expected2 = Ok(Instruction::TestEqual(
-            Value::RegisterId("#NERV".to_string()),
-            Value::RegisterId("X".to_string()),
+            Value::RegisterId("#NERV".into()),
+            Value::RegisterId("X".into()),
         ));
         let expected3 = Ok(Instruction::TestEqual(
-            Value::RegisterId("#NERV".to_string()),
+            Value::RegisterId("#NERV".into()),
             Value::Number(6666),
         ));
         let expected4 = Ok(Instruction::TestEqual(
@@ -514,11 +612,11 @@ mod tests {
         ));
         let expected5 = Ok(Instruction::TestGreaterThan(
             Value::Number(-9999),
-            Value::RegisterId("X".to_string()),
+            Value::RegisterId("X".into()),
         ));
         let expected6 = Ok(Instruction::TestLessThan(
-            Value::RegisterId("#NERV".to_string()),
-            Value::RegisterId("X".to_string()),
+            Value::RegisterId("#NERV".into()),
+            Value::RegisterId("X".into()),
         ));
         let expected_err1: Result<Instruction, ParseError> = Err(ParseError::InvalidValues);
         let expected_err2: Result<Instruction, ParseError> = Err(ParseError::InvalidInstruction);
@@ -594,8 +692,8 @@ mod tests {
         let invalid_instruction4 = "LINK Y";
 
         let expected1 = Ok(Instruction::Link(Value::Number(666)));
-        let expected2 = Ok(Instruction::Link(Value::RegisterId("X".to_string())));
-        let expected3 = Ok(Instruction::Link(Value::RegisterId("#NERV".to_string())));
+        let expected2 = Ok(Instruction::Link(Value::RegisterId("X".into())));
+        let expected3 = Ok(Instruction::Link(Value::RegisterId("#NERV".into())));
         let expected_err1: Result<Instruction, ParseError> = Err(ParseError::InvalidLineLength);
         let expected_err2: Result<Instruction, ParseError> = Err(ParseError::InvalidValues);
         let expected_err3: Result<Instruction, ParseError> = Err(ParseError::InvalidLineLength);
@@ -627,8 +725,8 @@ mod tests {
         let invalid_instruction3 = "HOST X #NERV";
         let invalid_instruction4 = "HOST Y";
 
-        let expected1 = Ok(Instruction::Host(Value::RegisterId("X".to_string())));
-        let expected2 = Ok(Instruction::Host(Value::RegisterId("#NERV".to_string())));
+        let expected1 = Ok(Instruction::Host(Value::RegisterId("X".into())));
+        let expected2 = Ok(Instruction::Host(Value::RegisterId("#NERV".into())));
         let expected_err1: Result<Instruction, ParseError> = Err(ParseError::InvalidValues);
         let expected_err2: Result<Instruction, ParseError> = Err(ParseError::InvalidValues);
 let expected_err3: Result<Instruction, ParseError> = Err(ParseError::InvalidLineLength);
@@ -710,4 +808,73 @@ let expected_err3: Result<Instruction, ParseError> = Err(ParseError::InvalidLine
         assert_eq!(err3, expected_err3);
         assert_eq!(err4, expected_err4);
     }
+
+    #[test]
+    fn test_display_round_trip() {
+        let lines = [
+            "COPY -9999 X",
+            "ADDI T X #NERV",
+            "SUBI 1 2 X",
+            "MULI 1 2 X",
+            "DIVI 1 2 X",
+            "MODI 1 2 X",
+            "SWIZ X 4321 T",
+            "MARK LABEL",
+            "JUMP LABEL",
+            "TJMP LABEL",
+            "FJMP LABEL",
+            "TEST X = 1",
+            "TEST X > 1",
+            "TEST X < 1",
+            "REPL LABEL",
+            "HALT",
+            "KILL",
+            "LINK 800",
+            "HOST X",
+            "MODE",
+            "VOID M",
+            "TEST MRD",
+            "MAKE",
+            "GRAB 200",
+            "FILE X",
+            "SEEK -9999",
+            "VOID F",
+            "DROP",
+            "WIPE",
+            "TEST EOF",
+            "NOTE",
+            "NOOP",
+            "RAND 0 10 X",
+        ];
+
+        for line in lines {
+            let instruction: Instruction = line.parse().unwrap();
+
+            assert_eq!(instruction.to_string(), line);
+            assert_eq!(instruction.to_string().parse(), Ok(instruction));
+        }
+    }
+
+    #[test]
+    fn test_display_note_drops_comment() {
+        let instruction: Instruction = "NOTE THIS IS A COMMENT".parse().unwrap();
+
+        assert_eq!(instruction.to_string(), "NOTE");
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde_round_trip() {
+        let instructions = vec![
+            Instruction::Copy(Value::Number(-9999), Value::RegisterId("X".into())),
+            Instruction::Mark(Value::LabelId("LABEL".into())),
+            Instruction::TestMRD,
+            Instruction::Halt,
+        ];
+
+        let json = serde_json::to_string(&instructions).unwrap();
+        let round_trip: Vec<Instruction> = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(round_trip, instructions);
+    }
 }
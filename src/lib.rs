@@ -0,0 +1,13 @@
+pub mod analysis;
+#[cfg(feature = "proptest")]
+pub mod arbitrary;
+pub mod data_file;
+pub mod diagnostic;
+pub mod error;
+pub mod exa;
+pub mod instruction;
+pub mod minify;
+mod naming;
+pub mod preprocessor;
+pub mod random;
+pub mod value;
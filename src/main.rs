@@ -1,14 +1,10 @@
-pub mod exa;
-pub mod instruction;
-pub mod value;
-
-use crate::instruction::Instruction;
-use crate::value::Value;
+use exapunks_lite::instruction::Instruction;
+use exapunks_lite::value::Value;
 
 fn main() {
     let lhs = Value::Number(-127);
     let rhs = Value::Number(128);
-    let destination = Value::RegisterId("X".to_string());
+    let destination = Value::RegisterId("X".into());
     let add_instruction = Instruction::Add(lhs.clone(), rhs, destination);
 
     println!("Hello, world!");
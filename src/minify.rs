@@ -0,0 +1,116 @@
+use std::collections::BTreeMap;
+
+use crate::error::ExapunksError;
+use crate::instruction::{Instruction, LineParseError};
+use crate::naming::name_from_index;
+use crate::value::Value;
+
+/// The characters used for short labels, in the order they are handed out: 0 through 9, then A
+/// through Z, then two characters, and so on.
+const LABEL_CHARACTERS: &[u8] = b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZ";
+
+/// Returns a smaller copy of the program by dropping everything that doesn't do work.
+///
+/// * NOTE and NOOP instructions are removed.
+/// * Labels are renamed to the shortest available ids, in order of first appearance.
+///
+/// Removing a NOOP saves a cycle, so a program that uses NOOPs to line up timing with another
+/// EXA (e.g. over M) may behave differently afterwards.
+///
+/// # Examples
+///
+/// ```
+/// # use exapunks_lite::instruction::Instruction;
+/// # use exapunks_lite::minify::minify;
+/// let program: Vec<Instruction> = ["NOTE COUNT TO TEN", "MARK COUNTER", "ADDI X 1 X", "NOOP"]
+///     .iter()
+///     .map(|line| line.parse().unwrap())
+///     .collect();
+///
+/// let minified: Vec<String> = minify(&program).iter().map(ToString::to_string).collect();
+///
+/// assert_eq!(minified, vec!["MARK 0", "ADDI X 1 X"]);
+/// ```
+#[must_use]
+pub fn minify(instructions: &[Instruction]) -> Vec<Instruction> {
+    let mut short_labels: BTreeMap<Value, Value> = BTreeMap::new();
+    let mut rename = |label: &Value| {
+        let next_index = short_labels.len();
+
+        short_labels
+            .entry(label.clone())
+            .or_insert_with(|| Value::LabelId(name_from_index(next_index, LABEL_CHARACTERS).into()))
+            .clone()
+    };
+
+    instructions
+        .iter()
+        .filter_map(|instruction| match instruction {
+            Instruction::Note | Instruction::NoOp => None,
+            Instruction::Mark(label) => Some(Instruction::Mark(rename(label))),
+            Instruction::Jump(label) => Some(Instruction::Jump(rename(label))),
+            Instruction::JumpIfTrue(label) => Some(Instruction::JumpIfTrue(rename(label))),
+            Instruction::JumpIfFalse(label) => Some(Instruction::JumpIfFalse(rename(label))),
+            Instruction::Replicate(label) => Some(Instruction::Replicate(rename(label))),
+            _ => Some(instruction.clone()),
+        })
+        .collect()
+}
+
+/// Parses the given source, minifies it with [`minify`], and writes it back out one instruction
+/// per line. Blank lines are skipped.
+///
+/// # Errors
+///
+/// Returns an error with the 1-based line number if any line fails to parse.
+pub fn minify_source(source: &str) -> Result<String, ExapunksError> {
+    let instructions = source
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(index, line)| {
+            line.parse().map_err(|error| LineParseError {
+                line: index + 1,
+                error,
+            })
+        })
+        .collect::<Result<Vec<Instruction>, _>>()?;
+
+    let lines: Vec<String> = minify(&instructions)
+        .iter()
+        .map(ToString::to_string)
+        .collect();
+
+    Ok(lines.join("\n"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::minify_source;
+    use crate::error::ExapunksError;
+    use crate::instruction::{LineParseError, ParseError};
+
+    #[test]
+    fn test_minify_source() {
+        let source = "NOTE FIND THE FILE\n\nGRAB 200\nMARK READ_LOOP\nCOPY F X\n\
+                      NOOP\nTEST EOF\nFJMP READ_LOOP\nREPL CLEAN_UP\nJUMP END\n\
+                      MARK CLEAN_UP\nWIPE\nMARK END";
+
+        let expected = "GRAB 200\nMARK 0\nCOPY F X\nTEST EOF\nFJMP 0\nREPL 1\nJUMP 2\n\
+                        MARK 1\nWIPE\nMARK 2";
+
+        assert_eq!(minify_source(source), Ok(expected.to_string()));
+    }
+
+    #[test]
+    fn test_minify_source_err() {
+        let source = "COPY 1 X\n\nCOPY X";
+
+        let expected = Err(ExapunksError::LineParse(LineParseError {
+            line: 3,
+            error: ParseError::InvalidLineLength,
+        }));
+
+        assert_eq!(minify_source(source), expected);
+    }
+}
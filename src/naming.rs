@@ -0,0 +1,33 @@
+/// Converts an index into a name built from the given alphabet, the way spreadsheet columns are
+/// named: every one-character name first, then every two-character name, and so on. With the
+/// alphabet "AB", 0 is A, 1 is B, 2 is AA, 3 is AB, and 4 is BA.
+pub(crate) fn name_from_index(index: usize, alphabet: &[u8]) -> String {
+    let base = alphabet.len();
+    let mut characters = Vec::new();
+    let mut remaining = index + 1;
+
+    while remaining > 0 {
+        remaining -= 1;
+        characters.push(char::from(alphabet[remaining % base]));
+        remaining /= base;
+    }
+
+    characters.iter().rev().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::name_from_index;
+
+    #[test]
+    fn test_name_from_index() {
+        let alphabet = b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZ";
+
+        assert_eq!(name_from_index(0, alphabet), "0");
+        assert_eq!(name_from_index(9, alphabet), "9");
+        assert_eq!(name_from_index(10, alphabet), "A");
+        assert_eq!(name_from_index(35, alphabet), "Z");
+        assert_eq!(name_from_index(36, alphabet), "00");
+        assert_eq!(name_from_index(37, alphabet), "01");
+    }
+}
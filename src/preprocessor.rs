@@ -0,0 +1,233 @@
+use std::error::Error;
+use std::fmt;
+
+use crate::diagnostic::{Diagnostic, DiagnosticCode};
+
+/// The reasons conditional assembly directives can fail to resolve.
+///
+/// Each error holds the 1-based line number of the offending directive.
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub enum PreprocessError {
+    /// An `@IF` without a flag.
+    MissingFlag(usize),
+    /// An `@ELSE` without an open `@IF`.
+    UnmatchedElse(usize),
+    /// A second `@ELSE` for the same `@IF`.
+    DuplicateElse(usize),
+    /// An `@END` without an open `@IF`.
+    UnmatchedEnd(usize),
+    /// An `@IF` that is never closed with `@END`.
+    UnterminatedIf(usize),
+    /// A line starting with `@` that isn't a known directive.
+    UnknownDirective(usize),
+    /// A directive followed by more words than it takes, e.g. `@IF A B` or `@END A`.
+    UnexpectedToken(usize),
+}
+
+impl PreprocessError {
+    /// Returns the 1-based line number of the offending directive.
+    #[must_use]
+    pub fn line(&self) -> usize {
+        match self {
+            Self::MissingFlag(line)
+            | Self::UnmatchedElse(line)
+            | Self::DuplicateElse(line)
+            | Self::UnmatchedEnd(line)
+            | Self::UnterminatedIf(line)
+            | Self::UnknownDirective(line)
+            | Self::UnexpectedToken(line) => *line,
+        }
+    }
+}
+
+impl Diagnostic for PreprocessError {
+    fn diagnostic_code(&self) -> DiagnosticCode {
+        match self {
+            Self::MissingFlag(_) => DiagnosticCode::MissingFlag,
+            Self::UnmatchedElse(_) => DiagnosticCode::UnmatchedElse,
+            Self::DuplicateElse(_) => DiagnosticCode::DuplicateElse,
+            Self::UnmatchedEnd(_) => DiagnosticCode::UnmatchedEnd,
+            Self::UnterminatedIf(_) => DiagnosticCode::UnterminatedIf,
+            Self::UnknownDirective(_) => DiagnosticCode::UnknownDirective,
+            Self::UnexpectedToken(_) => DiagnosticCode::UnexpectedToken,
+        }
+    }
+}
+
+impl fmt::Display for PreprocessError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line(), self.diagnostic_code())
+    }
+}
+
+impl Error for PreprocessError {}
+
+/// An open `@IF` block.
+struct Block {
+    line_number: usize,
+    is_parent_active: bool,
+    is_condition_met: bool,
+    has_else: bool,
+}
+
+impl Block {
+    fn is_active(&self) -> bool {
+        self.is_parent_active && (self.is_condition_met != self.has_else)
+    }
+}
+
+/// Resolves `@IF FLAG` / `@ELSE` / `@END` directives against the given flags.
+///
+/// Returns the lines that survive, paired with their 1-based line numbers in the original source
+/// so later diagnostics still point at the right place. Directive lines are never returned.
+/// Blocks can be nested.
+///
+/// # Errors
+///
+/// Returns an error if a directive is malformed, unknown, or unbalanced.
+///
+/// # Examples
+///
+/// ```
+/// # use exapunks_lite::preprocessor::preprocess;
+/// let source = "COPY 1 X\n@IF DEBUG\nCOPY X #DBUG\n@ELSE\nNOTE FAST\n@END\nHALT";
+///
+/// let debug = preprocess(source, &["DEBUG"]).unwrap();
+/// let release = preprocess(source, &[]).unwrap();
+///
+/// assert_eq!(debug, vec![(1, "COPY 1 X"), (3, "COPY X #DBUG"), (7, "HALT")]);
+/// assert_eq!(release, vec![(1, "COPY 1 X"), (5, "NOTE FAST"), (7, "HALT")]);
+/// ```
+pub fn preprocess<'a>(
+    source: &'a str,
+    flags: &[&str],
+) -> Result<Vec<(usize, &'a str)>, PreprocessError> {
+    let mut blocks: Vec<Block> = Vec::new();
+    let mut lines = Vec::new();
+
+    for (index, line) in source.lines().enumerate() {
+        let line_number = index + 1;
+        let is_active = blocks.last().is_none_or(Block::is_active);
+        let mut words = line.split_whitespace();
+
+        match words.next() {
+            Some("@IF") => {
+                let flag = words
+                    .next()
+                    .ok_or(PreprocessError::MissingFlag(line_number))?;
+
+                if words.next().is_some() {
+                    return Err(PreprocessError::UnexpectedToken(line_number));
+                }
+
+                blocks.push(Block {
+                    line_number,
+                    is_parent_active: is_active,
+                    is_condition_met: flags.contains(&flag),
+                    has_else: false,
+                });
+            }
+            Some("@ELSE" | "@END") if words.next().is_some() => {
+                return Err(PreprocessError::UnexpectedToken(line_number));
+            }
+            Some("@ELSE") => {
+                let block = blocks
+                    .last_mut()
+                    .ok_or(PreprocessError::UnmatchedElse(line_number))?;
+
+                if block.has_else {
+                    return Err(PreprocessError::DuplicateElse(line_number));
+                }
+
+                block.has_else = true;
+            }
+            Some("@END") => {
+                blocks
+                    .pop()
+                    .ok_or(PreprocessError::UnmatchedEnd(line_number))?;
+            }
+            Some(word) if word.starts_with('@') => {
+                return Err(PreprocessError::UnknownDirective(line_number));
+            }
+            _ if is_active => lines.push((line_number, line)),
+            _ => {}
+        }
+    }
+
+    match blocks.pop() {
+        Some(block) => Err(PreprocessError::UnterminatedIf(block.line_number)),
+        None => Ok(lines),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{preprocess, PreprocessError};
+
+    #[test]
+    fn test_no_directives() {
+        let source = "COPY 1 X\nHALT";
+
+        let expected = Ok(vec![(1, "COPY 1 X"), (2, "HALT")]);
+
+        assert_eq!(preprocess(source, &[]), expected);
+    }
+
+    #[test]
+    fn test_if_without_else() {
+        let source = "@IF DEBUG\nCOPY X #DBUG\n@END\nHALT";
+
+        let expected_with_flag = Ok(vec![(2, "COPY X #DBUG"), (4, "HALT")]);
+        let expected_without_flag = Ok(vec![(4, "HALT")]);
+
+        assert_eq!(preprocess(source, &["DEBUG"]), expected_with_flag);
+        assert_eq!(preprocess(source, &["OTHER"]), expected_without_flag);
+    }
+
+    #[test]
+    fn test_nested_blocks() {
+        let source = "@IF A\n@IF B\nAB\n@ELSE\nA\n@END\n@ELSE\n@IF B\nB\n@END\nNONE\n@END";
+
+        assert_eq!(preprocess(source, &["A", "B"]), Ok(vec![(3, "AB")]));
+        assert_eq!(preprocess(source, &["A"]), Ok(vec![(5, "A")]));
+        assert_eq!(preprocess(source, &["B"]), Ok(vec![(9, "B"), (11, "NONE")]));
+        assert_eq!(preprocess(source, &[]), Ok(vec![(11, "NONE")]));
+    }
+
+    #[test]
+    fn test_errors() {
+        assert_eq!(preprocess("@IF", &[]), Err(PreprocessError::MissingFlag(1)));
+        assert_eq!(
+            preprocess("HALT\n@ELSE", &[]),
+            Err(PreprocessError::UnmatchedElse(2))
+        );
+        assert_eq!(
+            preprocess("@IF A\n@ELSE\n@ELSE\n@END", &[]),
+            Err(PreprocessError::DuplicateElse(3))
+        );
+        assert_eq!(
+            preprocess("@END", &[]),
+            Err(PreprocessError::UnmatchedEnd(1))
+        );
+        assert_eq!(
+            preprocess("@IF A\n@IF B\n@END", &[]),
+            Err(PreprocessError::UnterminatedIf(1))
+        );
+        assert_eq!(
+            preprocess("@INCLUDE LIB", &[]),
+            Err(PreprocessError::UnknownDirective(1))
+        );
+        assert_eq!(
+            preprocess("@IF A B\n@END", &[]),
+            Err(PreprocessError::UnexpectedToken(1))
+        );
+        assert_eq!(
+            preprocess("@IF A\n@ELSE B\n@END", &[]),
+            Err(PreprocessError::UnexpectedToken(2))
+        );
+        assert_eq!(
+            preprocess("@IF A\n@END A", &[]),
+            Err(PreprocessError::UnexpectedToken(2))
+        );
+    }
+}
@@ -0,0 +1,94 @@
+/// A source of random numbers for RAND and anything else that needs to pick at random.
+///
+/// Simulations should draw from a single `Random` so runs can be replayed by reusing the seed.
+pub trait Random {
+    /// Returns the next raw 64-bit value.
+    fn next_u64(&mut self) -> u64;
+
+    /// Returns a number between `a` and `b`, inclusive, in either order, as RAND does.
+    fn next_in_range(&mut self, a: isize, b: isize) -> isize {
+        let (low, high) = if a <= b { (a, b) } else { (b, a) };
+        // The full isize range is one wider than u64 can count, so it loses its top value.
+        let span = u64::try_from(high.abs_diff(low))
+            .map_or(u64::MAX, |difference| difference.saturating_add(1));
+        let offset = usize::try_from(self.next_u64() % span).unwrap_or_default();
+
+        low.saturating_add_unsigned(offset)
+    }
+}
+
+/// A small, deterministic generator (`SplitMix64`) that produces the same sequence for the same
+/// seed on every platform.
+///
+/// # Examples
+///
+/// ```
+/// # use exapunks_lite::random::{Random, SeededRandom};
+/// let mut first = SeededRandom::seed(42);
+/// let mut second = SeededRandom::seed(42);
+///
+/// let value = first.next_in_range(-9999, 9999);
+///
+/// assert!((-9999..=9999).contains(&value));
+/// assert_eq!(second.next_in_range(-9999, 9999), value);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SeededRandom {
+    state: u64,
+}
+
+impl SeededRandom {
+    /// Creates a generator from the given seed.
+    #[must_use]
+    pub fn seed(seed: u64) -> Self {
+        SeededRandom { state: seed }
+    }
+}
+
+impl Random for SeededRandom {
+    fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+
+        let mut value = self.state;
+
+        value = (value ^ (value >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        value = (value ^ (value >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+
+        value ^ (value >> 31)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Random, SeededRandom};
+
+    #[test]
+    fn test_known_sequence() {
+        let mut random = SeededRandom::seed(0);
+
+        let expected = vec![
+            0xE220_A839_7B1D_CDAF,
+            0x6E78_9E6A_A1B9_65F4,
+            0x06C4_5D18_8009_454F,
+        ];
+
+        let result: Vec<u64> = (0..3).map(|_| random.next_u64()).collect();
+
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn test_next_in_range() {
+        let mut random = SeededRandom::seed(0);
+
+        for _ in 0..1000 {
+            let value = random.next_in_range(5, -3);
+
+            assert!((-3..=5).contains(&value));
+        }
+
+        assert_eq!(random.next_in_range(4, 4), 4);
+        assert!(random.next_in_range(-5, isize::MAX) >= -5);
+        assert!(random.next_in_range(isize::MAX, isize::MIN) < isize::MAX);
+    }
+}
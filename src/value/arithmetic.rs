@@ -0,0 +1,124 @@
+use std::error::Error;
+use std::fmt;
+
+use crate::diagnostic::{Diagnostic, DiagnosticCode};
+
+/// The largest number a register can hold.
+pub const MAX_NUMBER: isize = 9999;
+/// The smallest number a register can hold.
+pub const MIN_NUMBER: isize = -9999;
+
+/// Raised by DIVI or MODI with a divisor of 0. The game kills the [`Exa`] that runs it.
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct DivideByZeroError;
+
+impl Diagnostic for DivideByZeroError {
+    fn diagnostic_code(&self) -> DiagnosticCode {
+        DiagnosticCode::DivideByZero
+    }
+}
+
+impl fmt::Display for DivideByZeroError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.diagnostic_code())
+    }
+}
+
+impl Error for DivideByZeroError {}
+
+/// Clamps a number to the range a register can hold, -9999 to 9999.
+#[must_use]
+pub fn clamp_number(number: isize) -> isize {
+    number.clamp(MIN_NUMBER, MAX_NUMBER)
+}
+
+/// Divides as DIVI does: the quotient is truncated toward zero, then clamped.
+///
+/// # Errors
+///
+/// Returns an error if `divisor` is 0.
+///
+/// # Examples
+///
+/// ```
+/// # use exapunks_lite::value::{divide, DivideByZeroError};
+/// assert_eq!(divide(7, 2), Ok(3));
+/// assert_eq!(divide(-7, 2), Ok(-3));
+/// assert_eq!(divide(7, 0), Err(DivideByZeroError));
+/// ```
+pub fn divide(dividend: isize, divisor: isize) -> Result<isize, DivideByZeroError> {
+    if divisor == 0 {
+        return Err(DivideByZeroError);
+    }
+
+    // Only isize::MIN / -1 overflows, and its true quotient is positive and past the clamp.
+    Ok(dividend
+        .checked_div(divisor)
+        .map_or(MAX_NUMBER, clamp_number))
+}
+
+/// Takes the remainder as MODI does: the result has the sign of the divisor, so `-7 MODI 3` is 2
+/// and `7 MODI -3` is -2.
+///
+/// # Errors
+///
+/// Returns an error if `divisor` is 0.
+///
+/// # Examples
+///
+/// ```
+/// # use exapunks_lite::value::{modulo, DivideByZeroError};
+/// assert_eq!(modulo(7, 3), Ok(1));
+/// assert_eq!(modulo(-7, 3), Ok(2));
+/// assert_eq!(modulo(7, -3), Ok(-2));
+/// assert_eq!(modulo(7, 0), Err(DivideByZeroError));
+/// ```
+pub fn modulo(dividend: isize, divisor: isize) -> Result<isize, DivideByZeroError> {
+    if divisor == 0 {
+        return Err(DivideByZeroError);
+    }
+
+    // Only isize::MIN % -1 overflows, and its true remainder is 0.
+    let remainder = dividend.wrapping_rem(divisor);
+
+    if remainder != 0 && (remainder < 0) != (divisor < 0) {
+        Ok(clamp_number(remainder + divisor))
+    } else {
+        Ok(clamp_number(remainder))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{clamp_number, divide, modulo, DivideByZeroError};
+
+    #[test]
+    fn test_clamp_number() {
+        assert_eq!(clamp_number(10000), 9999);
+        assert_eq!(clamp_number(-10000), -9999);
+        assert_eq!(clamp_number(42), 42);
+    }
+
+    #[test]
+    fn test_divide() {
+        assert_eq!(divide(9, 3), Ok(3));
+        assert_eq!(divide(-9, 2), Ok(-4));
+        assert_eq!(divide(9, -2), Ok(-4));
+        assert_eq!(divide(-9, -2), Ok(4));
+        assert_eq!(divide(0, 5), Ok(0));
+        assert_eq!(divide(0, 0), Err(DivideByZeroError));
+        assert_eq!(divide(isize::MIN, -1), Ok(9999));
+        assert_eq!(divide(isize::MIN, 1), Ok(-9999));
+    }
+
+    #[test]
+    fn test_modulo() {
+        assert_eq!(modulo(9, 3), Ok(0));
+        assert_eq!(modulo(-9, 3), Ok(0));
+        assert_eq!(modulo(-1, 10), Ok(9));
+        assert_eq!(modulo(1, -10), Ok(-9));
+        assert_eq!(modulo(-1, -10), Ok(-1));
+        assert_eq!(modulo(5, 0), Err(DivideByZeroError));
+        assert_eq!(modulo(isize::MIN, -1), Ok(0));
+    }
+}
@@ -1,7 +1,20 @@
+mod arithmetic;
+mod small_string;
+
 use std::cmp::{Eq, Ord, PartialEq, PartialOrd};
-use std::convert::From;
+use std::convert::{From, TryFrom};
+use std::error::Error;
+use std::fmt;
 use std::str::FromStr;
 
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+pub use arithmetic::{clamp_number, divide, modulo, DivideByZeroError, MAX_NUMBER, MIN_NUMBER};
+pub use small_string::SmallString;
+
+use crate::diagnostic::{Diagnostic, DiagnosticCode};
+
 /// A `Value` is used to hold several types of information: number, keyword, register id, and a
 /// label id. Each type is used by an [`Exa`] to perform their tasks. Whether it is storing keywords
 /// from a [`Register`] to a [`File`], or asking their [`Program`] to jump to a specific label. Or
@@ -12,21 +25,56 @@ use std::str::FromStr;
 ///
 /// A [`Register`] can hold on to a number or keyword value.
 #[derive(Debug, Clone, Eq, PartialEq, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum Value {
     /// A number.
     Number(isize),
     /// A keyword.
-    Keyword(String),
+    Keyword(SmallString),
     /// The Id of a [`Register`].
-    RegisterId(String),
+    RegisterId(SmallString),
     /// The Id of label in the [`Program`].
-    LabelId(String),
+    LabelId(SmallString),
 }
 
 /// A dummy struct to indicate that there was an error on the [`FromStr`] implementation.
 #[derive(Debug, Eq, PartialEq, Clone)]
 pub struct ParseError;
 
+impl Diagnostic for ParseError {
+    fn diagnostic_code(&self) -> DiagnosticCode {
+        DiagnosticCode::InvalidValue
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.diagnostic_code())
+    }
+}
+
+impl Error for ParseError {}
+
+/// Indicates that a non-number [`Value`] was converted into an `isize`.
+///
+/// The original value is kept so the caller can report or recover it.
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct NotANumberError(pub Value);
+
+impl Diagnostic for NotANumberError {
+    fn diagnostic_code(&self) -> DiagnosticCode {
+        DiagnosticCode::NotANumber
+    }
+}
+
+impl fmt::Display for NotANumberError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.diagnostic_code(), self.0)
+    }
+}
+
+impl Error for NotANumberError {}
+
 impl Value {
     /// Tries to return a [`Value::Number`] or [`Value::RegisterId`] from the given input.
     ///
@@ -43,19 +91,20 @@ impl Value {
     /// # Examples
     ///
     /// ```
+    /// # use exapunks_lite::value::Value;
     /// let number = "-9999";
     /// let hardware_register_id = "#NERV";
     /// let exa_register_id = "X";
     /// let empty = "";
     ///
     /// let expected_number_result = Ok(Value::Number(-9999));
-    /// let expected_hardware_register_id_result = Ok(Value::RegisterId("#NERV".to_string()));
-    /// let expected_exa_register_id_result = Ok(Value::RegisterId("X".to_string()));
+    /// let expected_hardware_register_id_result = Ok(Value::RegisterId("#NERV".into()));
+    /// let expected_exa_register_id_result = Ok(Value::RegisterId("X".into()));
     ///
     /// let number_result = Value::new_number_or_register_id(number);
     /// let hardware_register_id_result = Value::new_number_or_register_id(hardware_register_id);
     /// let exa_register_id_result = Value::new_number_or_register_id(exa_register_id);
-    /// let empty_result = Value::new_number_or_register_id(exa_register_id);
+    /// let empty_result = Value::new_number_or_register_id(empty);
     ///
     /// assert_eq!(number_result, expected_number_result);
     /// assert_eq!(
@@ -66,10 +115,9 @@ impl Value {
     /// assert!(empty_result.is_err());
     /// ```
     pub fn new_number_or_register_id(input: &str) -> Result<Self, ParseError> {
-        match input.parse::<Value>() {
-            Ok(Self::Number(number)) => Ok(Self::Number(number)),
-            Ok(Self::Keyword(keyword)) => Self::new_register_id(&keyword),
-            _ => Err(ParseError),
+        match input.parse::<isize>() {
+            Ok(number) => Ok(Self::Number(number)),
+            Err(_) => Self::new_register_id(input),
         }
     }
 
@@ -87,18 +135,19 @@ impl Value {
     /// # Examples
     ///
     /// ```
+    /// # use exapunks_lite::value::Value;
     /// let number = "-9999";
     /// let hardware_register_id = "#NERV";
     /// let exa_register_id = "X";
     /// let empty = "";
     ///
-    /// let expected_hardware_register_id_result = Ok(Value::RegisterId("#NERV".to_string()));
-    /// let expected_exa_register_id_result = Ok(Value::RegisterId("X".to_string()));
+    /// let expected_hardware_register_id_result = Ok(Value::RegisterId("#NERV".into()));
+    /// let expected_exa_register_id_result = Ok(Value::RegisterId("X".into()));
     ///
-    /// let number_result = Value::new_number_or_register_id(number);
-    /// let hardware_register_id_result = Value::new_number_or_register_id(hardware_register_id);
-    /// let exa_register_id_result = Value::new_number_or_register_id(exa_register_id);
-    /// let empty_result = Value::new_number_or_register_id(exa_register_id);
+    /// let number_result = Value::new_register_id(number);
+    /// let hardware_register_id_result = Value::new_register_id(hardware_register_id);
+    /// let exa_register_id_result = Value::new_register_id(exa_register_id);
+    /// let empty_result = Value::new_register_id(empty);
     ///
     /// assert_eq!(
     ///     hardware_register_id_result,
@@ -114,7 +163,7 @@ impl Value {
         let is_valid_exa_register_id = matches!(input, "X" | "T" | "F" | "M");
 
         if is_valid_hardware_register_id || is_valid_exa_register_id {
-            Ok(Value::RegisterId(input.to_string()))
+            Ok(Value::RegisterId(input.into()))
         } else {
             Err(ParseError)
         }
@@ -129,10 +178,11 @@ impl Value {
     /// # Examples
     ///
     /// ```
+    /// # use exapunks_lite::value::Value;
     /// let id = "JUMP_TO_THIS";
     /// let empty = "";
     ///
-    /// let expected = Ok(Value::LabelId("JUMP_TO_THIS".to_string()));
+    /// let expected = Ok(Value::LabelId("JUMP_TO_THIS".into()));
     ///
     /// let result = Value::new_label_id(id);
     /// let empty_result = Value::new_label_id(empty);
@@ -144,7 +194,7 @@ impl Value {
         if input.is_empty() {
             Err(ParseError)
         } else {
-            Ok(Value::LabelId(input.to_string()))
+            Ok(Value::LabelId(input.into()))
         }
     }
 }
@@ -155,22 +205,24 @@ impl From<isize> for Value {
     }
 }
 
-impl From<Value> for isize {
-    fn from(input: Value) -> isize {
+impl TryFrom<Value> for isize {
+    type Error = NotANumberError;
+
+    fn try_from(input: Value) -> Result<isize, Self::Error> {
         match input {
-            Value::Number(number) => number,
-            _ => panic!("Cannot convert {input:?} into an isize!"),
+            Value::Number(number) => Ok(number),
+            _ => Err(NotANumberError(input)),
         }
     }
 }
 
-impl ToString for Value {
-    fn to_string(&self) -> String {
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
-            Self::Number(number) => number.to_string(),
-            Self::Keyword(keyword) => keyword.clone(),
-            Self::RegisterId(register_id) => register_id.clone(),
-            Self::LabelId(label_id) => label_id.clone(),
+            Self::Number(number) => write!(f, "{number}"),
+            Self::Keyword(keyword) => write!(f, "{keyword}"),
+            Self::RegisterId(register_id) => write!(f, "{register_id}"),
+            Self::LabelId(label_id) => write!(f, "{label_id}"),
         }
     }
 }
@@ -182,14 +234,14 @@ impl FromStr for Value {
         match s.parse::<isize>() {
             _ if s.is_empty() => Err(ParseError),
             Ok(number) => Ok(Value::Number(number)),
-            Err(_) => Ok(Value::Keyword(s.to_string())),
+            Err(_) => Ok(Value::Keyword(s.into())),
         }
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::Value;
+    use super::{NotANumberError, Value};
 
     #[test]
     fn test_new_number_or_register_id() {
@@ -198,8 +250,8 @@ mod tests {
         let exa_register_id = "X";
 
         let expected_number_result = Ok(Value::Number(-9999));
-        let expected_hardware_register_id_result = Ok(Value::RegisterId("#NERV".to_string()));
-        let expected_exa_register_id_result = Ok(Value::RegisterId("X".to_string()));
+        let expected_hardware_register_id_result = Ok(Value::RegisterId("#NERV".into()));
+        let expected_exa_register_id_result = Ok(Value::RegisterId("X".into()));
 
         let number_result = Value::new_number_or_register_id(number);
         let hardware_register_id_result = Value::new_number_or_register_id(hardware_register_id);
@@ -236,8 +288,8 @@ mod tests {
         let hardware_register_id = "#NERV";
         let exa_register_id = "X";
 
-        let expected_hardware_register_id_result = Ok(Value::RegisterId("#NERV".to_string()));
-        let expected_exa_register_id_result = Ok(Value::RegisterId("X".to_string()));
+        let expected_hardware_register_id_result = Ok(Value::RegisterId("#NERV".into()));
+        let expected_exa_register_id_result = Ok(Value::RegisterId("X".into()));
 
         let hardware_register_id_result = Value::new_register_id(hardware_register_id);
         let exa_register_id_result = Value::new_register_id(exa_register_id);
@@ -277,7 +329,7 @@ mod tests {
     fn test_new_labal_id() {
         let id = "JUMP_TO_THIS";
 
-        let expected = Ok(Value::LabelId("JUMP_TO_THIS".to_string()));
+        let expected = Ok(Value::LabelId("JUMP_TO_THIS".into()));
 
         let result = Value::new_label_id(id);
 
@@ -299,7 +351,7 @@ mod tests {
 
         let keyword = keyword_string.parse().unwrap();
 
-        assert_eq!(Value::Keyword("keyword".to_string()), keyword);
+        assert_eq!(Value::Keyword("keyword".into()), keyword);
     }
 
     #[test]
@@ -324,17 +376,20 @@ mod tests {
     fn test_to_from_number_and_isize() {
         let number = Value::Number(-127);
 
-        let number_value: isize = number.into();
+        let number_value: Result<isize, NotANumberError> = number.try_into();
 
-        assert_eq!(-127, number_value);
+        assert_eq!(Ok(-127), number_value);
     }
 
     #[test]
-    #[should_panic]
-    fn test_from_non_number_to_isize_panics() {
-        let keyword = Value::Keyword("keyword".to_string());
+    fn test_from_non_number_to_isize_err() {
+        let keyword = Value::Keyword("keyword".into());
+
+        let expected = Err(NotANumberError(keyword.clone()));
+
+        let to_isize: Result<isize, NotANumberError> = keyword.try_into();
 
-        let _to_isize: isize = keyword.into();
+        assert_eq!(to_isize, expected);
     }
 
     #[test]
@@ -348,10 +403,26 @@ mod tests {
 
     #[test]
     fn test_from_non_number_to_string() {
-        let keyword = Value::Keyword("keyword".to_string());
+        let keyword = Value::Keyword("keyword".into());
 
         let keyword_string: String = keyword.to_string();
 
         assert_eq!("keyword".to_string(), keyword_string);
     }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde_round_trip() {
+        let values = vec![
+            Value::Number(-9999),
+            Value::Keyword("keyword".into()),
+            Value::RegisterId("#NERV".into()),
+            Value::LabelId("LABEL".into()),
+        ];
+
+        let json = serde_json::to_string(&values).unwrap();
+        let round_trip: Vec<Value> = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(round_trip, values);
+    }
 }
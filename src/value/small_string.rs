@@ -0,0 +1,246 @@
+use std::borrow::Borrow;
+use std::cmp::Ordering;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::ops::Deref;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// The longest string, in bytes, that a [`SmallString`] can hold without allocating.
+const INLINE_CAPACITY: usize = 22;
+
+/// An immutable string that stores short contents inline instead of on the heap.
+///
+/// Keywords, register ids, and label ids are almost always only a few characters long, so a
+/// [`Value`](super::Value) holding one of these never needs to allocate. Strings longer than 22
+/// bytes fall back to a boxed `str`.
+///
+/// Comparison, ordering, and hashing all behave exactly like they do for the underlying `str`.
+#[derive(Clone)]
+pub struct SmallString(Repr);
+
+#[derive(Clone)]
+enum Repr {
+    Inline {
+        length: u8,
+        bytes: [u8; INLINE_CAPACITY],
+    },
+    Heap(Box<str>),
+}
+
+impl SmallString {
+    /// Returns the contents as a string slice.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use exapunks_lite::value::SmallString;
+    /// let id = SmallString::from("#NERV");
+    ///
+    /// assert_eq!(id.as_str(), "#NERV");
+    /// ```
+    #[must_use]
+    pub fn as_str(&self) -> &str {
+        match &self.0 {
+            // Inline bytes are only ever copied from a valid `str` of exactly `length` bytes, so
+            // this never falls back to the default.
+            Repr::Inline { length, bytes } => {
+                std::str::from_utf8(&bytes[..usize::from(*length)]).unwrap_or_default()
+            }
+            Repr::Heap(string) => string,
+        }
+    }
+
+    /// Returns true if the contents are stored inline, i.e. without a heap allocation.
+    #[must_use]
+    pub fn is_inline(&self) -> bool {
+        matches!(self.0, Repr::Inline { .. })
+    }
+}
+
+impl From<&str> for SmallString {
+    fn from(input: &str) -> Self {
+        match u8::try_from(input.len()) {
+            Ok(length) if input.len() <= INLINE_CAPACITY => {
+                let mut bytes = [0; INLINE_CAPACITY];
+
+                bytes[..input.len()].copy_from_slice(input.as_bytes());
+
+                SmallString(Repr::Inline { length, bytes })
+            }
+            _ => SmallString(Repr::Heap(input.into())),
+        }
+    }
+}
+
+impl From<String> for SmallString {
+    fn from(input: String) -> Self {
+        if input.len() <= INLINE_CAPACITY {
+            SmallString::from(input.as_str())
+        } else {
+            SmallString(Repr::Heap(input.into_boxed_str()))
+        }
+    }
+}
+
+impl From<SmallString> for String {
+    fn from(input: SmallString) -> String {
+        match input.0 {
+            Repr::Heap(string) => string.into_string(),
+            Repr::Inline { .. } => input.as_str().to_string(),
+        }
+    }
+}
+
+impl Deref for SmallString {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl AsRef<str> for SmallString {
+    fn as_ref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl Borrow<str> for SmallString {
+    fn borrow(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl PartialEq for SmallString {
+    fn eq(&self, other: &Self) -> bool {
+        self.as_str() == other.as_str()
+    }
+}
+
+impl Eq for SmallString {}
+
+impl PartialEq<str> for SmallString {
+    fn eq(&self, other: &str) -> bool {
+        self.as_str() == other
+    }
+}
+
+impl PartialEq<&str> for SmallString {
+    fn eq(&self, other: &&str) -> bool {
+        self.as_str() == *other
+    }
+}
+
+impl PartialOrd for SmallString {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for SmallString {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.as_str().cmp(other.as_str())
+    }
+}
+
+impl Hash for SmallString {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.as_str().hash(state);
+    }
+}
+
+impl fmt::Debug for SmallString {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self.as_str(), f)
+    }
+}
+
+impl fmt::Display for SmallString {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self.as_str(), f)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl Serialize for SmallString {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> Deserialize<'de> for SmallString {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer).map(SmallString::from)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{SmallString, INLINE_CAPACITY};
+
+    #[test]
+    fn test_short_string_is_inline() {
+        let id = SmallString::from("#NERV");
+
+        assert!(id.is_inline());
+        assert_eq!(id.as_str(), "#NERV");
+    }
+
+    #[test]
+    fn test_empty_string_is_inline() {
+        let empty = SmallString::from("");
+
+        assert!(empty.is_inline());
+        assert_eq!(empty.as_str(), "");
+    }
+
+    #[test]
+    fn test_string_at_capacity_is_inline() {
+        let input = "A".repeat(INLINE_CAPACITY);
+
+        let keyword = SmallString::from(input.as_str());
+
+        assert!(keyword.is_inline());
+        assert_eq!(keyword.as_str(), input);
+    }
+
+    #[test]
+    fn test_long_string_is_on_heap() {
+        let input = "A".repeat(INLINE_CAPACITY + 1);
+
+        let keyword = SmallString::from(input.clone());
+
+        assert!(!keyword.is_inline());
+        assert_eq!(keyword.as_str(), input);
+    }
+
+    #[test]
+    fn test_multibyte_characters() {
+        let keyword = SmallString::from("ÉXA☃");
+
+        assert!(keyword.is_inline());
+        assert_eq!(keyword.as_str(), "ÉXA☃");
+    }
+
+    #[test]
+    fn test_equality_and_ordering_match_str() {
+        let short = SmallString::from("A");
+        let long = SmallString::from("B".repeat(INLINE_CAPACITY + 1));
+
+        assert_eq!(short, "A");
+        assert_eq!(short, SmallString::from("A".to_string()));
+        assert!(short < long);
+    }
+
+    #[test]
+    fn test_into_string() {
+        let short = SmallString::from("X");
+        let long = SmallString::from("B".repeat(INLINE_CAPACITY + 1));
+
+        assert_eq!(String::from(short), "X");
+        assert_eq!(String::from(long), "B".repeat(INLINE_CAPACITY + 1));
+    }
+}
@@ -0,0 +1,73 @@
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+
+use exapunks_lite::instruction::Instruction;
+use exapunks_lite::value::Value;
+
+/// Wraps the system allocator to count the allocations made by the current thread, so tests
+/// running in parallel don't affect each other's counts.
+struct CountingAllocator;
+
+thread_local! {
+    static ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let _ = ALLOCATIONS.try_with(|count| count.set(count.get() + 1));
+
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+fn count_allocations<T>(f: impl FnOnce() -> T) -> (T, usize) {
+    let before = ALLOCATIONS.with(Cell::get);
+    let result = f();
+    let after = ALLOCATIONS.with(Cell::get);
+
+    (result, after - before)
+}
+
+#[test]
+fn test_parse_typical_instructions_does_not_allocate() {
+    let lines = [
+        "COPY -9999 X",
+        "ADDI T X #NERV",
+        "MARK LOOP",
+        "TEST #NERV < X",
+        "FJMP LOOP",
+        "LINK 800",
+        "HALT",
+    ];
+
+    for line in lines {
+        let (result, allocations) = count_allocations(|| line.parse::<Instruction>());
+
+        assert!(result.is_ok(), "{line} should parse");
+        assert_eq!(allocations, 0, "{line} allocated");
+    }
+}
+
+#[test]
+fn test_parse_keyword_does_not_allocate() {
+    let (result, allocations) = count_allocations(|| "KEYWORD".parse::<Value>());
+
+    assert_eq!(result, Ok(Value::Keyword("KEYWORD".into())));
+    assert_eq!(allocations, 0);
+}
+
+#[test]
+fn test_parse_long_keyword_allocates() {
+    let (result, allocations) =
+        count_allocations(|| "A_VERY_LONG_KEYWORD_THAT_IS_NOT_INLINE".parse::<Value>());
+
+    assert!(result.is_ok());
+    assert_eq!(allocations, 1);
+}